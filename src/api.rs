@@ -0,0 +1,1076 @@
+use crate::audit;
+use crate::cache;
+use crate::config::Config;
+use crate::error::CliError;
+use crate::models::*;
+use anyhow::{anyhow, Result};
+use log::{debug, info, warn};
+use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION, CONTENT_TYPE, RETRY_AFTER};
+use reqwest::{Client, StatusCode};
+use serde::de::DeserializeOwned;
+use serde_json::json;
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+use std::sync::Mutex;
+use std::time::Duration;
+
+// Used unless the config or --api-url points somewhere else, e.g. a regional endpoint
+pub const MONDAY_API_URL: &str = "https://api.monday.com/v2";
+/// Largest page of items Monday.com hands out in one request
+pub const MAX_PAGE_LIMIT: u32 = 500;
+// Used when a 429 response doesn't say how long to back off
+const DEFAULT_RATE_LIMIT_WAIT_SECONDS: u64 = 60;
+// How long an idle connection is kept open for the next request of the same command
+const IDLE_CONNECTION_SECONDS: u64 = 90;
+pub const USER_AGENT: &str = concat!("monday-claim/", env!("CARGO_PKG_VERSION"));
+
+/// The HTTP client every API request of a run goes through, so its connections are reused.
+///
+/// Applies the configured timeout and sends the user agent (see `Config::user_agent`), the JSON
+/// content type and the API key with every request.
+pub fn build_client(config: &Config) -> Result<Client> {
+    // Sensitive values are left out of the header's Debug output
+    let mut authorization = HeaderValue::from_str(config.api_key())
+        .map_err(|_| anyhow!("The API key contains characters that can't be sent"))?;
+    authorization.set_sensitive(true);
+    let mut headers = HeaderMap::new();
+    headers.insert(AUTHORIZATION, authorization);
+    headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+
+    Ok(Client::builder()
+        // Without a timeout a hanging server would block the CLI forever
+        .timeout(Duration::from_secs(config.timeout_seconds))
+        .user_agent(config.user_agent())
+        .default_headers(headers)
+        .pool_idle_timeout(Duration::from_secs(IDLE_CONNECTION_SECONDS))
+        .tcp_keepalive(Duration::from_secs(IDLE_CONNECTION_SECONDS))
+        .build()?)
+}
+
+/// Typed access to the Monday.com API for the configured board.
+///
+/// All requests go through `fetch_data`, so retries and GraphQL error handling live in one
+/// place. Mutations respect `dry_run`: the request is printed instead of sent and `None` is
+/// returned. With `audit_log` configured, every mutation sent is recorded there, see `audit`.
+pub struct MondayClient {
+    http: Client,
+    config: Config,
+    dry_run: bool,
+    // Ignore cached data that is still fresh, see `get_groups`
+    refresh_cache: bool,
+    // Record queries in the audit log too, not only mutations
+    audit_reads: bool,
+    // The subcommand being run, for the audit log; changes with every line of a `repl`
+    command: Mutex<Option<String>>,
+}
+
+impl MondayClient {
+    pub fn new(
+        config: Config,
+        dry_run: bool,
+        refresh_cache: bool,
+        audit_reads: bool,
+    ) -> Result<Self> {
+        let http = build_client(&config)?;
+        Ok(MondayClient {
+            http,
+            config,
+            dry_run,
+            refresh_cache,
+            audit_reads,
+            command: Mutex::new(None),
+        })
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Name the subcommand whose requests follow in the audit log.
+    pub fn set_command(&self, command: Option<&str>) {
+        *self.command.lock().unwrap() = command.map(str::to_string);
+    }
+
+    /// Fetch the board with its groups, columns and the first page of items, including their
+    /// subitems when asked for. Without `with_items` only the groups and columns are fetched.
+    ///
+    /// Archived items are left out unless `include_archived` is set; the page's cursor keeps
+    /// that choice for the pages that follow.
+    pub async fn get_board_structure(
+        &self,
+        limit: u32,
+        with_items: bool,
+        with_subitems: bool,
+        include_archived: bool,
+        column_ids: Option<&[String]>,
+    ) -> Result<Board> {
+        let query = r#"
+            query GetBoardStructure($boardId: [ID!], $limit: Int!, $withItems: Boolean!, $withSubitems: Boolean!, $queryParams: ItemsQuery, $columnIds: [String!]) {
+                boards(ids: $boardId) {
+                    name
+                    id
+                    groups {
+                        id
+                        title
+                    }
+                    columns {
+                        id
+                        type
+                        settings_str
+                    }
+                    items_page(limit: $limit, query_params: $queryParams) @include(if: $withItems) {
+                        cursor
+                        items {
+                            id
+                            name
+                            state
+                            group {
+                                id
+                            }
+                            column_values(ids: $columnIds) {
+                                id
+                                value
+                            }
+                            subitems @include(if: $withSubitems) {
+                                id
+                                name
+                                column_values {
+                                    id
+                                    value
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        "#;
+
+        let variables = json!({
+            "boardId": [self.config.board_id],
+            "limit": limit,
+            "withItems": with_items,
+            "withSubitems": with_subitems,
+            "queryParams": include_archived.then(include_archived_params),
+            "columnIds": column_ids
+        });
+        let request = GraphQLRequest {
+            query: query.to_string(),
+            variables: Some(variables),
+        };
+
+        info!("Sending query to Monday.com API to get board structure...");
+
+        let data: BoardStructureResponse = self.fetch_data(&request, "board").await?;
+        only_board(data.boards, self.config.board_id)
+    }
+
+    /// Fetch the page of items following `cursor`.
+    pub async fn next_items_page(
+        &self,
+        limit: u32,
+        cursor: &str,
+        with_subitems: bool,
+        column_ids: Option<&[String]>,
+    ) -> Result<ItemsPage> {
+        let query = r#"
+            query GetNextItemsPage($limit: Int!, $cursor: String!, $withSubitems: Boolean!, $columnIds: [String!]) {
+                next_items_page(limit: $limit, cursor: $cursor) {
+                    cursor
+                    items {
+                        id
+                        name
+                        state
+                        group {
+                            id
+                        }
+                        column_values(ids: $columnIds) {
+                            id
+                            value
+                        }
+                        subitems @include(if: $withSubitems) {
+                            id
+                            name
+                            column_values {
+                                id
+                                value
+                            }
+                        }
+                    }
+                }
+            }
+        "#;
+
+        let variables = json!({
+            "limit": limit,
+            "cursor": cursor,
+            "withSubitems": with_subitems,
+            "columnIds": column_ids
+        });
+        let request = GraphQLRequest {
+            query: query.to_string(),
+            variables: Some(variables),
+        };
+
+        let data: NextItemsPageResponse = self.fetch_data(&request, "next page").await?;
+        Ok(data.next_items_page)
+    }
+
+    /// Fetch every item whose date column holds the given day, following pagination.
+    pub async fn get_items_on_date(&self, date: &str) -> Result<Vec<Item>> {
+        let query = r#"
+            query GetItemsOnDate($boardId: [ID!], $columnId: ID!, $compareValue: CompareValue!) {
+                boards(ids: $boardId) {
+                    items_page(
+                        limit: 500,
+                        query_params: {rules: [{column_id: $columnId, compare_value: $compareValue}]}
+                    ) {
+                        cursor
+                        items {
+                            id
+                            name
+                            group {
+                                id
+                            }
+                            column_values {
+                                id
+                                value
+                            }
+                        }
+                    }
+                }
+            }
+        "#;
+
+        let request = GraphQLRequest {
+            query: query.to_string(),
+            variables: Some(json!({
+                "boardId": [self.config.board_id],
+                "columnId": self.config.columns.date,
+                "compareValue": ["EXACT", date]
+            })),
+        };
+
+        let data: BoardItemsResponse = self.fetch_data(&request, "items by date").await?;
+        let mut page = only_board(data.boards, self.config.board_id)?.items_page;
+
+        let mut items = std::mem::take(&mut page.items);
+        let mut cursor = page.cursor;
+        while let Some(current) = cursor {
+            let page = self
+                .next_items_page(MAX_PAGE_LIMIT, &current, false, None)
+                .await?;
+            items.extend(page.items);
+            cursor = page.cursor;
+        }
+        Ok(items)
+    }
+
+    /// Groups of the configured board, read from the cache while it's fresh.
+    pub async fn get_groups(&self) -> Result<Vec<Group>> {
+        if let Some(groups) = self.fresh_cached_groups() {
+            return Ok(groups);
+        }
+        self.fetch_and_cache_groups().await
+    }
+
+    /// Like `get_groups`, but fetches again when the title isn't among the cached groups, in
+    /// case the group was created since they were cached.
+    pub async fn get_groups_including(&self, title: &str) -> Result<Vec<Group>> {
+        if let Some(groups) = self.fresh_cached_groups() {
+            if groups.iter().any(|group| group.title == title) {
+                return Ok(groups);
+            }
+            debug!("Group {} is not cached, fetching the groups again", title);
+        }
+        self.fetch_and_cache_groups().await
+    }
+
+    fn fresh_cached_groups(&self) -> Option<Vec<Group>> {
+        if self.refresh_cache {
+            return None;
+        }
+        let cached = cache::load(self.config.board_id)?;
+        let ttl = Duration::from_secs(self.config.cache_ttl_minutes * 60);
+        if cached.age() >= ttl {
+            return None;
+        }
+        debug!(
+            "Using groups cached {} ago",
+            cache::describe_age(cached.age())
+        );
+        Some(cached.groups)
+    }
+
+    /// Fetch the groups and cache them, falling back to a stale cache when offline.
+    async fn fetch_and_cache_groups(&self) -> Result<Vec<Group>> {
+        match self.fetch_groups().await {
+            Ok(groups) => {
+                if let Err(e) = cache::store_groups(self.config.board_id, &groups) {
+                    warn!("Could not cache the board's groups: {}", e);
+                }
+                Ok(groups)
+            }
+            Err(e) if is_network_error(&e) => match cache::load(self.config.board_id) {
+                Some(cached) => {
+                    warn!(
+                        "Could not reach Monday.com, using groups cached {} ago",
+                        cache::describe_age(cached.age())
+                    );
+                    Ok(cached.groups)
+                }
+                None => Err(e),
+            },
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn fetch_groups(&self) -> Result<Vec<Group>> {
+        let query = r#"
+            query GetBoardGroups($boardId: [ID!]) {
+                boards(ids: $boardId) {
+                    groups {
+                        id
+                        title
+                    }
+                }
+            }
+        "#;
+
+        let request = GraphQLRequest {
+            query: query.to_string(),
+            variables: Some(json!({ "boardId": [self.config.board_id] })),
+        };
+
+        let data: BoardGroupsResponse = self.fetch_data(&request, "group").await?;
+        only_board(data.boards, self.config.board_id).map(|board| board.groups)
+    }
+
+    /// Fetch the columns of the configured board.
+    pub async fn get_columns(&self) -> Result<Vec<BoardColumn>> {
+        let query = r#"
+            query GetBoardColumns($boardId: [ID!]) {
+                boards(ids: $boardId) {
+                    columns {
+                        id
+                        title
+                        type
+                    }
+                }
+            }
+        "#;
+
+        let request = GraphQLRequest {
+            query: query.to_string(),
+            variables: Some(json!({ "boardId": [self.config.board_id] })),
+        };
+
+        let data: BoardColumnsResponse = self.fetch_data(&request, "columns").await?;
+        only_board(data.boards, self.config.board_id).map(|board| board.columns)
+    }
+
+    /// Find the group with the given title, failing with `CliError::GroupNotFound` if the board
+    /// has none.
+    pub async fn find_group_by_title(&self, title: &str) -> Result<Group> {
+        info!("Looking up group ID for year: {}", title);
+        let groups = self.get_groups_including(title).await?;
+        let group = group_by_title(&groups, title)?;
+        info!("Found group ID: {} for year: {}", group.id, title);
+        Ok(group.clone())
+    }
+
+    /// Look up an item's name and current group.
+    pub async fn get_item_summary(&self, item_id: &str) -> Result<ItemSummary> {
+        let query = r#"
+            query GetItemSummary($itemId: [ID!]) {
+                items(ids: $itemId) {
+                    id
+                    name
+                    group {
+                        id
+                        title
+                    }
+                }
+            }
+        "#;
+
+        let request = GraphQLRequest {
+            query: query.to_string(),
+            variables: Some(json!({ "itemId": [item_id] })),
+        };
+
+        let data: ItemSummaryResponse = self.fetch_data(&request, "item").await?;
+        data.items
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("No item found with ID: {}", item_id))
+    }
+
+    /// Fetch a single item with its column values and its board's groups and columns.
+    pub async fn get_item(
+        &self,
+        item_id: &str,
+        column_ids: Option<&[String]>,
+    ) -> Result<ItemDetails> {
+        let query = r#"
+            query GetItem($itemId: [ID!], $columnIds: [String!]) {
+                items(ids: $itemId) {
+                    id
+                    name
+                    group {
+                        id
+                    }
+                    column_values(ids: $columnIds) {
+                        id
+                        value
+                    }
+                    board {
+                        groups {
+                            id
+                            title
+                        }
+                        columns {
+                            id
+                            type
+                            settings_str
+                        }
+                    }
+                }
+            }
+        "#;
+
+        let variables = json!({ "itemId": [item_id], "columnIds": column_ids });
+        let request = GraphQLRequest {
+            query: query.to_string(),
+            variables: Some(variables),
+        };
+
+        let data: ItemDetailsResponse = self.fetch_data(&request, "item").await?;
+        data.items
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("No item found with ID: {}", item_id))
+    }
+
+    /// Names of the given users, keyed by ID; unknown IDs are simply missing.
+    pub async fn get_user_names(&self, ids: &BTreeSet<i64>) -> Result<HashMap<i64, String>> {
+        let query = r#"
+            query GetUsers($ids: [ID!]) {
+                users(ids: $ids) {
+                    id
+                    name
+                }
+            }
+        "#;
+
+        let request = GraphQLRequest {
+            query: query.to_string(),
+            variables: Some(json!({ "ids": ids })),
+        };
+
+        let data: UsersResponse = self.fetch_data(&request, "users").await?;
+        Ok(data
+            .users
+            .into_iter()
+            .filter_map(|user| user.id.parse::<i64>().ok().map(|id| (id, user.name)))
+            .collect())
+    }
+
+    /// The user the API key belongs to.
+    pub async fn me(&self) -> Result<User> {
+        let request = GraphQLRequest {
+            query: "query { me { id name } }".to_string(),
+            variables: None,
+        };
+
+        let data: MeResponse = self.fetch_data(&request, "me").await?;
+        Ok(data.me)
+    }
+
+    /// ID and name of the configured board, `None` if the API key can't see it.
+    pub async fn get_board_summary(&self) -> Result<Option<BoardSummary>> {
+        let request = GraphQLRequest {
+            query: r#"
+                query CheckBoard($boardId: [ID!]) {
+                    boards(ids: $boardId) {
+                        id
+                        name
+                    }
+                }
+            "#
+            .to_string(),
+            variables: Some(json!({ "boardId": [self.config.board_id] })),
+        };
+
+        let data: BoardSummaryResponse = self.fetch_data(&request, "board").await?;
+        Ok(data.boards.into_iter().next())
+    }
+
+    /// Set the board ID from the configured `board_name` if no ID was configured.
+    ///
+    /// The resolution is cached, so the boards are only listed the first time a name is used.
+    pub async fn resolve_board_name(&mut self) -> Result<()> {
+        if self.config.board_id != 0 {
+            return Ok(());
+        }
+        let Some(name) = self.config.board_name.clone() else {
+            return Ok(());
+        };
+        if let Some(board_id) = cache::board_id_for_name(&name) {
+            debug!("Board {} is cached with ID {}", name, board_id);
+            self.config.board_id = board_id;
+            return Ok(());
+        }
+
+        info!("Looking up the ID of board {}", name);
+        let matching: Vec<BoardSummary> = self
+            .get_boards()
+            .await?
+            .into_iter()
+            .filter(|board| board.name == name)
+            .collect();
+        let board = match matching.as_slice() {
+            [] => return Err(CliError::BoardNameNotFound(name).into()),
+            [board] => board,
+            boards => {
+                let mut message = format!(
+                    "{} boards are named {}, set board_id to the one you mean:",
+                    boards.len(),
+                    name
+                );
+                for board in boards {
+                    message.push_str(&format!("\n  - {}: {}", board.name, board.id));
+                }
+                return Err(CliError::InvalidInput(message).into());
+            }
+        };
+
+        let board_id = board
+            .id
+            .parse::<u64>()
+            .map_err(|_| anyhow!("Monday returned a non-numeric board ID: {}", board.id))?;
+        info!("Found board ID: {} for board {}", board_id, name);
+        if let Err(e) = cache::store_board_id_for_name(&name, board_id) {
+            warn!("Could not cache the board ID: {}", e);
+        }
+        self.config.board_id = board_id;
+        Ok(())
+    }
+
+    /// List every board the API key can see, page by page.
+    async fn get_boards(&self) -> Result<Vec<BoardSummary>> {
+        const PAGE_SIZE: u32 = 100;
+        let query = r#"
+            query GetBoards($limit: Int!, $page: Int!) {
+                boards(limit: $limit, page: $page) {
+                    id
+                    name
+                }
+            }
+        "#;
+
+        let mut boards = Vec::new();
+        // Pages are numbered from 1
+        for page in 1.. {
+            let request = GraphQLRequest {
+                query: query.to_string(),
+                variables: Some(json!({ "limit": PAGE_SIZE, "page": page })),
+            };
+            let data: BoardSummaryResponse = self.fetch_data(&request, "boards").await?;
+            let count = data.boards.len();
+            boards.extend(data.boards);
+            if count < PAGE_SIZE as usize {
+                break;
+            }
+        }
+        Ok(boards)
+    }
+
+    /// Create an item in the given group, `column_values` maps column IDs to their values.
+    pub async fn create_item(
+        &self,
+        group_id: &str,
+        name: &str,
+        column_values: &serde_json::Value,
+    ) -> Result<Option<CreatedItem>> {
+        let query = r#"
+            mutation CreateItem($boardId: ID!, $groupId: String!, $itemName: String!, $columnValues: JSON!) {
+                create_item(
+                    board_id: $boardId,
+                    group_id: $groupId,
+                    item_name: $itemName,
+                    column_values: $columnValues
+                ) {
+                    id
+                    name
+                }
+            }
+        "#;
+
+        let request = GraphQLRequest {
+            query: query.to_string(),
+            variables: Some(json!({
+                "boardId": self.config.board_id,
+                "groupId": group_id,
+                "itemName": name,
+                "columnValues": column_values.to_string()
+            })),
+        };
+
+        let data: Option<CreateItemResponse> = self.mutate(&request, "create").await?;
+        Ok(data.map(|data| data.create_item))
+    }
+
+    /// Create a group with the given title and return its ID.
+    pub async fn create_group(&self, title: &str) -> Result<Option<String>> {
+        let query = r#"
+            mutation CreateGroup($boardId: ID!, $groupName: String!) {
+                create_group(board_id: $boardId, group_name: $groupName) {
+                    id
+                }
+            }
+        "#;
+
+        let request = GraphQLRequest {
+            query: query.to_string(),
+            variables: Some(json!({
+                "boardId": self.config.board_id,
+                "groupName": title
+            })),
+        };
+
+        let data: Option<CreateGroupResponse> = self.mutate(&request, "group").await?;
+        Ok(data.map(|data| data.create_group.id))
+    }
+
+    pub async fn move_item_to_group(
+        &self,
+        item_id: &str,
+        group_id: &str,
+    ) -> Result<Option<ItemSummary>> {
+        let query = r#"
+            mutation MoveItem($itemId: ID!, $groupId: String!) {
+                move_item_to_group(item_id: $itemId, group_id: $groupId) {
+                    id
+                    name
+                    group {
+                        id
+                        title
+                    }
+                }
+            }
+        "#;
+
+        let request = GraphQLRequest {
+            query: query.to_string(),
+            variables: Some(json!({
+                "itemId": item_id,
+                "groupId": group_id
+            })),
+        };
+
+        let data: Option<MoveItemResponse> = self.mutate(&request, "move").await?;
+        Ok(data.map(|data| data.move_item_to_group))
+    }
+
+    pub async fn duplicate_item(&self, item_id: &str) -> Result<Option<CreatedItem>> {
+        let query = r#"
+            mutation DuplicateItem($boardId: ID!, $itemId: ID!) {
+                duplicate_item(board_id: $boardId, item_id: $itemId) {
+                    id
+                    name
+                }
+            }
+        "#;
+
+        let request = GraphQLRequest {
+            query: query.to_string(),
+            variables: Some(json!({
+                "boardId": self.config.board_id,
+                "itemId": item_id
+            })),
+        };
+
+        let data: Option<DuplicateItemResponse> = self.mutate(&request, "duplicate").await?;
+        Ok(data.map(|data| data.duplicate_item))
+    }
+
+    pub async fn delete_item(&self, item_id: &str) -> Result<Option<CreatedItem>> {
+        let query = r#"
+            mutation DeleteItem($itemId: ID!) {
+                delete_item(item_id: $itemId) {
+                    id
+                    name
+                }
+            }
+        "#;
+
+        let request = GraphQLRequest {
+            query: query.to_string(),
+            variables: Some(json!({ "itemId": item_id })),
+        };
+
+        let data: Option<DeleteItemResponse> = self.mutate(&request, "delete").await?;
+        Ok(data.map(|data| data.delete_item))
+    }
+
+    /// Set a single column of an item, `value` is the column's JSON value.
+    pub async fn change_column_value(
+        &self,
+        item_id: &str,
+        column_id: &str,
+        value: &serde_json::Value,
+    ) -> Result<Option<()>> {
+        let query = r#"
+            mutation ChangeColumnValue($boardId: ID!, $itemId: ID!, $columnId: String!, $value: JSON!) {
+                change_column_value(board_id: $boardId, item_id: $itemId, column_id: $columnId, value: $value) {
+                    id
+                }
+            }
+        "#;
+
+        let request = GraphQLRequest {
+            query: query.to_string(),
+            variables: Some(json!({
+                "boardId": self.config.board_id,
+                "itemId": item_id,
+                "columnId": column_id,
+                "value": value.to_string()
+            })),
+        };
+
+        let data: Option<serde_json::Value> = self.mutate(&request, "column update").await?;
+        Ok(data.map(|_| ()))
+    }
+
+    /// Set a column from a plain string such as `"7.5"`, returning the item's ID and name.
+    pub async fn change_simple_column_value(
+        &self,
+        item_id: &str,
+        column_id: &str,
+        value: &str,
+    ) -> Result<Option<CreatedItem>> {
+        let query = r#"
+            mutation ChangeSimpleColumnValue($boardId: ID!, $itemId: ID!, $columnId: String!, $value: String!) {
+                change_simple_column_value(board_id: $boardId, item_id: $itemId, column_id: $columnId, value: $value) {
+                    id
+                    name
+                }
+            }
+        "#;
+
+        let request = GraphQLRequest {
+            query: query.to_string(),
+            variables: Some(json!({
+                "boardId": self.config.board_id,
+                "itemId": item_id,
+                "columnId": column_id,
+                "value": value
+            })),
+        };
+
+        let data: Option<ChangeSimpleColumnValueResponse> =
+            self.mutate(&request, "column update").await?;
+        Ok(data.map(|data| data.change_simple_column_value))
+    }
+
+    /// Set several columns of an item at once, `column_values` maps column IDs to their values.
+    pub async fn change_multiple_column_values(
+        &self,
+        item_id: &str,
+        column_values: &serde_json::Value,
+    ) -> Result<Option<CreatedItem>> {
+        let query = r#"
+            mutation ChangeMultipleColumnValues($boardId: ID!, $itemId: ID!, $columnValues: JSON!) {
+                change_multiple_column_values(board_id: $boardId, item_id: $itemId, column_values: $columnValues) {
+                    id
+                    name
+                }
+            }
+        "#;
+
+        let request = GraphQLRequest {
+            query: query.to_string(),
+            variables: Some(json!({
+                "boardId": self.config.board_id,
+                "itemId": item_id,
+                "columnValues": column_values.to_string()
+            })),
+        };
+
+        let data: Option<ChangeMultipleColumnValuesResponse> =
+            self.mutate(&request, "column update").await?;
+        Ok(data.map(|data| data.change_multiple_column_values))
+    }
+
+    /// Send a mutation, or only print it for a dry run.
+    async fn mutate<T: DeserializeOwned>(
+        &self,
+        request: &GraphQLRequest,
+        what: &str,
+    ) -> Result<Option<T>> {
+        if self.dry_run {
+            print_dry_run(&self.config.api_url, request)?;
+            return Ok(None);
+        }
+        self.fetch_data(request, what).await.map(Some)
+    }
+
+    /// Send a query and deserialize its `data`, turning GraphQL errors into an `Err`.
+    ///
+    /// `what` names the response in error messages, e.g. "users".
+    pub async fn fetch_data<T: DeserializeOwned>(
+        &self,
+        request: &GraphQLRequest,
+        what: &str,
+    ) -> Result<T> {
+        let response_text = match self.post_with_retry(request).await {
+            Ok(response_text) => response_text,
+            Err(e) => {
+                self.audit(request, None, Some(&e));
+                return Err(e);
+            }
+        };
+        let result = self.parse_response(&response_text, what);
+        self.audit(request, Some(&response_text), result.as_ref().err());
+        result
+    }
+
+    fn parse_response<T: DeserializeOwned>(&self, response_text: &str, what: &str) -> Result<T> {
+        debug!("{} response: {}", what, self.config.redact(response_text));
+
+        let response: GraphQLResponse<T> = serde_json::from_str(response_text).map_err(|e| {
+            anyhow!(
+                "Failed to parse {} response: {}\nRaw response was: {}",
+                what,
+                e,
+                self.config.redact(response_text)
+            )
+        })?;
+
+        if let Some(errors) = response.errors {
+            for error in &errors {
+                if let Some(error_data) = error
+                    .extensions
+                    .as_ref()
+                    .and_then(|e| e.error_data.as_ref())
+                {
+                    debug!("Error data for \"{}\": {}", error.message, error_data);
+                }
+            }
+            return Err(CliError::GraphQL(errors).into());
+        }
+
+        response
+            .data
+            .ok_or_else(|| anyhow!("No data returned from API for the {} query", what))
+    }
+
+    /// Append a sent request and its outcome to the audit log, if one is configured. Queries
+    /// are only recorded with `audit_reads`.
+    fn audit(
+        &self,
+        request: &GraphQLRequest,
+        response_text: Option<&str>,
+        error: Option<&anyhow::Error>,
+    ) {
+        let Some(path) = &self.config.audit_log else {
+            return;
+        };
+        let is_mutation = request.query.trim_start().starts_with("mutation");
+        if !is_mutation && !self.audit_reads {
+            return;
+        }
+        let variables = request
+            .variables
+            .as_ref()
+            .and_then(|variables| {
+                serde_json::from_str(&self.config.redact(&variables.to_string())).ok()
+            })
+            .unwrap_or_default();
+        let entry = audit::Entry {
+            timestamp: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+            command: self.command.lock().unwrap().clone(),
+            operation: audit::operation_name(&request.query),
+            variables,
+            id: response_text
+                .filter(|_| is_mutation && error.is_none())
+                .and_then(audit::returned_id),
+            error: error.map(|e| self.config.redact(&format!("{:#}", e))),
+        };
+        if let Err(e) = audit::append(path, &entry) {
+            // The change is made already, failing the command now would only hide that
+            warn!("Could not record the request in the audit log: {}", e);
+        }
+    }
+
+    /// Send a GraphQL request and return the response body, retrying transient failures.
+    ///
+    /// Network errors and 5xx responses are retried up to `config.max_retries` times with
+    /// exponential backoff (500ms, 1s, 2s, ...). A 429 is retried once after the `Retry-After`
+    /// delay; any other non-success status fails immediately with an `HttpStatusError`.
+    async fn post_with_retry(&self, request: &GraphQLRequest) -> Result<String> {
+        let max_retries = self.config.max_retries;
+        let mut attempt = 0;
+        let mut rate_limited = false;
+        loop {
+            let result = self
+                .http
+                .post(&self.config.api_url)
+                .json(request)
+                .send()
+                .await;
+
+            let (failure, network_error) = match result {
+                Ok(response)
+                    if response.status() == StatusCode::TOO_MANY_REQUESTS && !rate_limited =>
+                {
+                    // Honor the server's requested wait once before giving up
+                    let wait_seconds = response
+                        .headers()
+                        .get(RETRY_AFTER)
+                        .and_then(|value| value.to_str().ok())
+                        .and_then(|value| value.trim().parse::<u64>().ok())
+                        .unwrap_or(DEFAULT_RATE_LIMIT_WAIT_SECONDS);
+                    warn!("Rate limited, waiting {}s", wait_seconds);
+                    tokio::time::sleep(Duration::from_secs(wait_seconds)).await;
+                    rate_limited = true;
+                    continue;
+                }
+                Ok(response) if response.status().is_server_error() => {
+                    (format!("Monday API returned {}", response.status()), false)
+                }
+                Ok(response) if response.status().is_success() => {
+                    return Ok(response.text().await?)
+                }
+                Ok(response) => {
+                    // Taken before the body, which consumes the response
+                    let status = response.status();
+                    let body = response.text().await.unwrap_or_default();
+                    return Err(HttpStatusError {
+                        status,
+                        body: self.config.redact(body.trim()),
+                    }
+                    .into());
+                }
+                Err(e) if e.is_timeout() => (
+                    format!("Request timed out after {}s", self.config.timeout_seconds),
+                    true,
+                ),
+                Err(e) => (self.config.redact(&format!("Request failed: {}", e)), true),
+            };
+
+            if attempt >= max_retries {
+                let message = format!("{} (giving up after {} retries)", failure, attempt);
+                // Typed so callers can tell that we're offline, see `is_network_error`
+                return Err(if network_error {
+                    NetworkError(message).into()
+                } else {
+                    anyhow!(message)
+                });
+            }
+
+            let delay = Duration::from_millis(500 * 2u64.pow(attempt));
+            attempt += 1;
+            warn!(
+                "{}, retrying in {}ms ({}/{})",
+                failure,
+                delay.as_millis(),
+                attempt,
+                max_retries
+            );
+            tokio::time::sleep(delay).await;
+        }
+    }
+}
+
+/// A request that never got an answer, e.g. because the network is down.
+#[derive(Debug)]
+struct NetworkError(String);
+
+impl fmt::Display for NetworkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for NetworkError {}
+
+pub fn is_network_error(error: &anyhow::Error) -> bool {
+    error.downcast_ref::<NetworkError>().is_some()
+}
+
+/// A response with a status that isn't worth retrying, e.g. 401 for a wrong API key.
+#[derive(Debug)]
+struct HttpStatusError {
+    status: StatusCode,
+    body: String,
+}
+
+impl fmt::Display for HttpStatusError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Monday API returned {}", self.status)?;
+        if !self.body.is_empty() {
+            write!(f, ": {}", self.body)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for HttpStatusError {}
+
+/// A suggestion for errors caused by the HTTP status of a response, where there is one.
+pub fn http_status_hint(error: &anyhow::Error) -> Option<&'static str> {
+    match error.downcast_ref::<HttpStatusError>()?.status {
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Some(
+            "Check that the API key is valid and has access to the board (`monday-claim check`)",
+        ),
+        _ => None,
+    }
+}
+
+/// The board a query asked for by ID. GraphQL errors, e.g. an unauthorized key, have already
+/// failed the request by now, so no board means a wrong ID or one the key's user can't see.
+fn only_board<T>(boards: Vec<T>, board_id: u64) -> Result<T> {
+    boards
+        .into_iter()
+        .next()
+        .ok_or_else(|| CliError::BoardNotFound(board_id).into())
+}
+
+/// Query parameters for `items_page` matching items in any state rather than only active ones.
+fn include_archived_params() -> serde_json::Value {
+    json!({
+        "rules": [{
+            "column_id": "__item_state__",
+            "compare_value": ["active", "archived"],
+            "operator": "any_of"
+        }]
+    })
+}
+
+/// Find the group with the given title, the error lists the groups that do exist.
+pub fn group_by_title<'a>(groups: &'a [Group], title: &str) -> Result<&'a Group, CliError> {
+    groups
+        .iter()
+        .find(|group| group.title == title)
+        .ok_or_else(|| {
+            let mut message = format!("No group found with title: {}\nAvailable groups:", title);
+            for group in groups {
+                message.push_str(&format!("\n  - {}: {}", group.title, group.id));
+            }
+            CliError::GroupNotFound(message)
+        })
+}
+
+/// Show the request a mutation would send, used by `--dry-run` instead of posting it.
+fn print_dry_run(api_url: &str, request: &GraphQLRequest) -> Result<()> {
+    println!(
+        "🔍 Dry run, the following request would be sent to {}:",
+        api_url
+    );
+    println!("Query:\n{}", request.query.trim());
+    if let Some(variables) = &request.variables {
+        println!("Variables:\n{}", serde_json::to_string_pretty(variables)?);
+    }
+    Ok(())
+}
@@ -0,0 +1,61 @@
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+
+/// One line of the `audit_log` file: a request sent to the API and how it ended.
+#[derive(Debug, Serialize)]
+pub struct Entry<'a> {
+    // RFC 3339 in UTC, so the entries of different machines sort together
+    pub timestamp: String,
+    // The subcommand that sent the request, e.g. "add"
+    pub command: Option<String>,
+    // The GraphQL operation, e.g. "CreateItem", none for an anonymous query
+    pub operation: Option<&'a str>,
+    pub variables: serde_json::Value,
+    // What a mutation returned the ID of: the item, or the group for a CreateGroup
+    pub id: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Add the entry to the end of the log as a line of JSON, creating the file if needed.
+pub fn append(path: &Path, entry: &Entry) -> Result<()> {
+    let mut line = serde_json::to_string(entry)?;
+    line.push('\n');
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| anyhow!("Failed to open {}: {}", path.display(), e))?;
+    // A single write, so the lines of concurrent requests don't interleave
+    file.write_all(line.as_bytes())
+        .map_err(|e| anyhow!("Failed to write to {}: {}", path.display(), e))
+}
+
+/// The name of the operation in a GraphQL document, e.g. "CreateItem" for
+/// `mutation CreateItem($boardId: ID!, ...) { ... }`.
+pub fn operation_name(query: &str) -> Option<&str> {
+    query
+        .split_whitespace()
+        .nth(1)
+        .and_then(|name| name.split(['(', '{']).next())
+        .filter(|name| !name.is_empty())
+}
+
+/// The `id` of the object a mutation's response returned, e.g. 555 for
+/// `{"data":{"create_item":{"id":"555"}}}`.
+pub fn returned_id(response_text: &str) -> Option<String> {
+    let response: serde_json::Value = serde_json::from_str(response_text).ok()?;
+    match response
+        .get("data")?
+        .as_object()?
+        .values()
+        .next()?
+        .get("id")?
+    {
+        serde_json::Value::String(id) => Some(id.clone()),
+        serde_json::Value::Number(id) => Some(id.to_string()),
+        _ => None,
+    }
+}
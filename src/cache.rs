@@ -0,0 +1,176 @@
+use crate::models::{Board, Group};
+use anyhow::{anyhow, Result};
+use directories::ProjectDirs;
+use log::debug;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// What is remembered about a board between runs, stored as JSON per board ID.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BoardCache {
+    // Seconds since the Unix epoch
+    pub fetched_at: u64,
+    pub groups: Vec<Group>,
+}
+
+impl BoardCache {
+    pub fn age(&self) -> Duration {
+        Duration::from_secs(now().saturating_sub(self.fetched_at))
+    }
+}
+
+/// Directory holding the cache files, e.g. `$XDG_CACHE_HOME/monday-claim` on Linux.
+pub fn cache_dir() -> Option<PathBuf> {
+    ProjectDirs::from("", "", "monday-claim").map(|dirs| dirs.cache_dir().to_path_buf())
+}
+
+fn board_path(board_id: u64) -> Option<PathBuf> {
+    cache_dir().map(|dir| dir.join(format!("board-{}.json", board_id)))
+}
+
+/// The cached data for a board, `None` if there is none or it can't be read.
+pub fn load(board_id: u64) -> Option<BoardCache> {
+    let path = board_path(board_id)?;
+    let content = fs::read_to_string(&path).ok()?;
+    match serde_json::from_str(&content) {
+        Ok(cache) => Some(cache),
+        Err(e) => {
+            debug!("Ignoring unreadable cache {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+pub fn store_groups(board_id: u64, groups: &[Group]) -> Result<()> {
+    let path = board_path(board_id).ok_or_else(|| anyhow!("No cache directory available"))?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let cache = BoardCache {
+        fetched_at: now(),
+        groups: groups.to_vec(),
+    };
+    fs::write(&path, serde_json::to_string_pretty(&cache)?)?;
+    debug!("Cached {} groups in {}", groups.len(), path.display());
+    Ok(())
+}
+
+/// The board and items of the last online `query`, shown again by `query --offline`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ItemsCache {
+    // Seconds since the Unix epoch
+    pub fetched_at: u64,
+    pub board: Board,
+}
+
+impl ItemsCache {
+    pub fn age(&self) -> Duration {
+        Duration::from_secs(now().saturating_sub(self.fetched_at))
+    }
+}
+
+fn items_path(board_id: u64) -> Option<PathBuf> {
+    cache_dir().map(|dir| dir.join(format!("items-{}.json", board_id)))
+}
+
+/// The items cached by the last online `query`, `None` if there are none or they can't be read.
+pub fn load_items(board_id: u64) -> Option<ItemsCache> {
+    let path = items_path(board_id)?;
+    let content = fs::read_to_string(&path).ok()?;
+    match serde_json::from_str(&content) {
+        Ok(cache) => Some(cache),
+        Err(e) => {
+            debug!("Ignoring unreadable cache {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+pub fn store_items(board_id: u64, board: &Board) -> Result<()> {
+    let path = items_path(board_id).ok_or_else(|| anyhow!("No cache directory available"))?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    // Same layout as `ItemsCache`, without having to own the board
+    let cache = serde_json::json!({ "fetched_at": now(), "board": board });
+    fs::write(&path, serde_json::to_string(&cache)?)?;
+    debug!(
+        "Cached {} items in {}",
+        board.items_page.items.len(),
+        path.display()
+    );
+    Ok(())
+}
+
+fn board_names_path() -> Option<PathBuf> {
+    cache_dir().map(|dir| dir.join("board-names.json"))
+}
+
+fn load_board_names() -> BTreeMap<String, u64> {
+    board_names_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// The ID a board name was resolved to before, board IDs never change so this doesn't expire.
+pub fn board_id_for_name(name: &str) -> Option<u64> {
+    load_board_names().get(name).copied()
+}
+
+pub fn store_board_id_for_name(name: &str, board_id: u64) -> Result<()> {
+    let path = board_names_path().ok_or_else(|| anyhow!("No cache directory available"))?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut names = load_board_names();
+    names.insert(name.to_string(), board_id);
+    fs::write(&path, serde_json::to_string_pretty(&names)?)?;
+    Ok(())
+}
+
+/// Remove every cache file, returning how many there were: the board groups, the items and the
+/// board IDs looked up by name. The state file lives in the config directory and is kept.
+pub fn clear() -> Result<usize> {
+    let Some(dir) = cache_dir() else {
+        return Ok(0);
+    };
+    let entries = match fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(anyhow!("Failed to read {}: {}", dir.display(), e)),
+    };
+
+    let mut removed = 0;
+    for entry in entries {
+        let path = entry?.path();
+        if path
+            .extension()
+            .is_some_and(|extension| extension == "json")
+        {
+            fs::remove_file(&path)?;
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+/// Human-readable age such as "5 minutes" or "3 hours".
+pub fn describe_age(age: Duration) -> String {
+    let minutes = age.as_secs() / 60;
+    match minutes {
+        0 => "less than a minute".to_string(),
+        1..=119 => format!("{} minutes", minutes),
+        120..=2879 => format!("{} hours", minutes / 60),
+        _ => format!("{} days", minutes / 1440),
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
@@ -1,18 +1,548 @@
-use serde::Deserialize;
+use crate::error::CliError;
+use anyhow::{anyhow, bail};
+use chrono::{Local, NaiveDate, Utc};
+use chrono_tz::Tz;
+use directories::ProjectDirs;
+use serde::{de, Deserialize, Deserializer};
+use std::collections::BTreeMap;
+use std::env;
 use std::fs;
+use std::path::{Path, PathBuf};
+
+// Activity keywords and the index of the matching label in the status column
+const ACTIVITIES: [(&str, i64); 9] = [
+    ("vacation", 0),
+    ("billable", 1),
+    ("holding", 2),
+    ("education", 3),
+    ("work_reduction", 4),
+    ("tbd", 5),
+    ("holiday", 6),
+    ("", 7),
+    ("illness", 8),
+];
+const VALID_ACTIVITIES: &str =
+    "vacation, billable, holding, education, work_reduction, tbd, holiday, illness (or an index 0-8)";
+const DEFAULT_MAX_DAILY_HOURS: f64 = 24.0;
+const DEFAULT_HOURS: f64 = 8.0;
+// Written by `init`, every optional setting is commented out
+const TEMPLATE: &str = include_str!("config_template.toml");
 
 #[derive(Debug, Deserialize)]
 pub struct Config {
-    pub api_key: String,
-    pub board_id: String,
-    pub user_id: String,
+    // Optional so it can be kept out of the file entirely, "keyring" reads it from the
+    // keychain, see `resolve`
+    #[serde(default)]
+    pub api_key: Option<String>,
+    // Numeric IDs, written either as "123" or 123; 0 means not configured until `resolve`
+    #[serde(default, deserialize_with = "deserialize_id")]
+    pub board_id: u64,
+    // Looked up through the API when board_id isn't set, see `MondayClient::resolve_board_name`
+    #[serde(default)]
+    pub board_name: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_id")]
+    pub user_id: u64,
+    // How often to retry transient API failures, overridden by --max-retries
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+    // How often a page of items too complex for Monday is asked for again with half the limit
+    #[serde(default = "default_complexity_retries")]
+    pub complexity_retries: u32,
+    // Activity keyword -> status index, for boards whose status labels differ from the defaults
+    #[serde(default)]
+    pub activities: BTreeMap<String, i64>,
+    #[serde(default)]
+    pub columns: ColumnMapping,
+    // Seconds to wait for each API request, overridden by --timeout
+    #[serde(default = "default_timeout_seconds")]
+    pub timeout_seconds: u64,
+    // How long fetched groups are reused before asking the API again, 0 disables the cache
+    #[serde(default = "default_cache_ttl_minutes")]
+    pub cache_ttl_minutes: u64,
+    // Warn after `add` when a day's total goes above this, see `max_daily_hours()`
+    #[serde(default)]
+    pub max_daily_hours: Option<f64>,
+    // Logged by `add` when --hours is omitted, see `default_hours()`
+    #[serde(default)]
+    pub default_hours: Option<f64>,
+    // GraphQL endpoint, overridden by --api-url; e.g. a regional endpoint or a local mock
+    #[serde(default = "default_api_url")]
+    pub api_url: String,
+    // Used by `add` when --name is omitted, overridden by --name-template
+    #[serde(default)]
+    pub name_template: Option<String>,
+    // Named sets of add values, picked with `add --template`
+    #[serde(default)]
+    pub templates: Vec<EntryTemplate>,
+    // Check the [columns] IDs against the board before running a command, also set by --strict
+    #[serde(default)]
+    pub strict: bool,
+    // IANA name such as "Europe/Rome" that decides which day is today, see `today()`
+    #[serde(default)]
+    pub timezone: Option<String>,
+    // Column ID -> how its values are shown, see `ColumnTransform`
+    #[serde(default)]
+    pub transforms: BTreeMap<String, ColumnTransform>,
+    // Added after monday-claim/<version> in the User-Agent, e.g. to tell an org's traffic apart
+    #[serde(default)]
+    pub user_agent_suffix: Option<String>,
+    // File that every mutation sent is appended to as a line of JSON, see `audit`
+    #[serde(default)]
+    pub audit_log: Option<PathBuf>,
     // Removed group_id field
 }
 
+/// Values that `add --template NAME` fills in for the options not given, see the
+/// `[[templates]]` config section.
+#[derive(Debug, Deserialize)]
+pub struct EntryTemplate {
+    pub name: String,
+    #[serde(default)]
+    pub activity: Option<String>,
+    #[serde(default)]
+    pub client: Option<String>,
+    #[serde(default)]
+    pub wi: Option<String>,
+}
+
+/// How the values of a column are shown instead of the usual decoding, see the `[transforms]`
+/// config section. An unknown name fails when the config is loaded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ColumnTransform {
+    // Only the address of a link column
+    Url,
+    // The JSON text exactly as Monday sent it
+    Raw,
+    // Everything up to the first whitespace of the usual value
+    FirstWord,
+}
+
+/// IDs of the board columns the tool reads and writes, see the `[columns]` config section.
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct ColumnMapping {
+    pub client: String,
+    pub work_item: String,
+    pub hours: String,
+    pub date: String,
+    pub status: String,
+    pub person: String,
+}
+
+impl Default for ColumnMapping {
+    fn default() -> Self {
+        ColumnMapping {
+            client: "text__1".to_string(),
+            work_item: "text8__1".to_string(),
+            hours: "numbers__1".to_string(),
+            date: "date4".to_string(),
+            status: "status".to_string(),
+            person: "person".to_string(),
+        }
+    }
+}
+
+impl ColumnMapping {
+    /// Each `[columns]` setting with the column ID it points at.
+    pub fn settings(&self) -> [(&'static str, &str); 6] {
+        [
+            ("client", &self.client),
+            ("work_item", &self.work_item),
+            ("hours", &self.hours),
+            ("date", &self.date),
+            ("status", &self.status),
+            ("person", &self.person),
+        ]
+    }
+
+    /// Replace some of the column IDs from a `--column-map` list such as
+    /// `client=text5,hours=numbers2`, keyed by the `[columns]` setting names.
+    pub fn override_with(&mut self, map: &str) -> Result<(), String> {
+        let names: Vec<&str> = self.settings().iter().map(|(name, _)| *name).collect();
+        let names = names.join(", ");
+        for pair in map
+            .split(',')
+            .map(str::trim)
+            .filter(|pair| !pair.is_empty())
+        {
+            let (setting, column_id) = pair
+                .split_once('=')
+                .map(|(setting, column_id)| (setting.trim(), column_id.trim()))
+                .ok_or_else(|| format!("expected SETTING=COLUMN_ID, got `{}`", pair))?;
+            if column_id.is_empty() {
+                return Err(format!("no column ID given for `{}`", setting));
+            }
+            let target = match setting {
+                "client" => &mut self.client,
+                "work_item" => &mut self.work_item,
+                "hours" => &mut self.hours,
+                "date" => &mut self.date,
+                "status" => &mut self.status,
+                "person" => &mut self.person,
+                _ => {
+                    return Err(format!(
+                        "unknown setting `{}`, expected one of: {}",
+                        setting, names
+                    ))
+                }
+            };
+            *target = column_id.to_string();
+        }
+        Ok(())
+    }
+}
+
 impl Config {
-    pub fn from_file(path: &str) -> anyhow::Result<Self> {
+    /// Load and resolve the config, `keyring` and `board_id` being the --keyring and
+    /// --board-id overrides.
+    pub fn from_file(
+        path: impl AsRef<Path>,
+        keyring: bool,
+        board_id: Option<u64>,
+    ) -> anyhow::Result<Self> {
         let content = fs::read_to_string(path)?;
-        let config: Config = toml::from_str(&content)?;
+        let mut config: Config = toml::from_str(&content)?;
+        if keyring {
+            config.api_key = Some(crate::secrets::KEYRING_API_KEY.to_string());
+        }
+        config.resolve(board_id)?;
+        config.validate()?;
         Ok(config)
     }
+
+    /// Locate the config file when none was given on the command line.
+    ///
+    /// Searched in order: `$MONDAY_CLAIM_CONFIG`, `./monday-claim.toml`, then `config.toml` in
+    /// the platform config directory (e.g. `$XDG_CONFIG_HOME/monday-claim` on Linux).
+    pub fn find_default_path() -> anyhow::Result<PathBuf> {
+        let mut candidates = Vec::new();
+        if let Some(path) = env_var("MONDAY_CLAIM_CONFIG") {
+            candidates.push(PathBuf::from(path));
+        }
+        candidates.push(PathBuf::from("monday-claim.toml"));
+        if let Some(path) = Self::user_config_path() {
+            candidates.push(path);
+        }
+
+        if let Some(path) = candidates.iter().find(|path| path.is_file()) {
+            return Ok(path.clone());
+        }
+
+        let searched: Vec<String> = candidates
+            .iter()
+            .map(|path| format!("  - {}", path.display()))
+            .collect();
+        bail!(
+            "No config file found, pass one with --config or create one of:\n{}",
+            searched.join("\n")
+        )
+    }
+
+    /// `config.toml` in the platform config directory, the last place searched for a config.
+    pub fn user_config_path() -> Option<PathBuf> {
+        ProjectDirs::from("", "", "monday-claim").map(|dirs| dirs.config_dir().join("config.toml"))
+    }
+
+    /// Write a commented config template to `path`, refusing to replace a file unless `force`.
+    pub fn write_template(path: &Path, force: bool) -> anyhow::Result<()> {
+        if path.exists() && !force {
+            bail!(
+                "{} already exists, pass --force to replace it",
+                path.display()
+            );
+        }
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)
+                .map_err(|e| anyhow!("Failed to create {}: {}", dir.display(), e))?;
+        }
+        fs::write(path, TEMPLATE).map_err(|e| anyhow!("Failed to write {}: {}", path.display(), e))
+    }
+
+    /// Fill in values from the environment and check that everything required is present.
+    ///
+    /// `MONDAY_API_KEY` is used when the file has no `api_key`, while `MONDAY_BOARD_ID` and
+    /// `MONDAY_USER_ID` take precedence over the values in the file, and `board_id` over both.
+    /// An `api_key` of `"keyring"` is replaced by the key `login` stored in the keychain.
+    pub fn resolve(&mut self, board_id: Option<u64>) -> anyhow::Result<()> {
+        if self.api_key.as_deref() == Some(crate::secrets::KEYRING_API_KEY) {
+            self.api_key = Some(crate::secrets::load_api_key()?);
+        } else if self.api_key.as_deref().is_none_or(str::is_empty) {
+            self.api_key = env_var("MONDAY_API_KEY");
+        }
+        if let Some(board_id) = env_var("MONDAY_BOARD_ID") {
+            self.board_id = parse_id(&board_id).map_err(|e| anyhow!("MONDAY_BOARD_ID: {}", e))?;
+        }
+        if let Some(board_id) = board_id {
+            self.board_id = board_id;
+            self.board_name = None;
+        }
+        if let Some(user_id) = env_var("MONDAY_USER_ID") {
+            self.user_id = parse_id(&user_id).map_err(|e| anyhow!("MONDAY_USER_ID: {}", e))?;
+        }
+
+        if self.api_key.is_none() {
+            bail!(
+                "No API key configured: set `api_key` in the config file or the MONDAY_API_KEY environment variable, or store one with `monday-claim login`"
+            );
+        }
+        if self.board_id == 0 && self.board_name.as_deref().is_none_or(str::is_empty) {
+            bail!(
+                "No board configured: set `board_id` or `board_name` in the config file or the MONDAY_BOARD_ID environment variable"
+            );
+        }
+        if self.user_id == 0 {
+            bail!(
+                "No user ID configured: set `user_id` in the config file or the MONDAY_USER_ID environment variable"
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Check values that would otherwise only fail once they are sent to the API.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if !(self.api_url.starts_with("https://") || self.api_url.starts_with("http://")) {
+            bail!(
+                "Invalid api_url `{}`: must start with https:// or http://",
+                self.api_url
+            );
+        }
+        if self.timeout_seconds == 0 {
+            bail!("Invalid timeout_seconds 0: must be at least 1");
+        }
+        if let Some(max) = self.max_daily_hours {
+            if !(max.is_finite() && max > 0.0) {
+                bail!("Invalid max_daily_hours {}: must be a positive number", max);
+            }
+        }
+        if let Some(hours) = self.default_hours {
+            if !(hours.is_finite() && hours >= 0.0) {
+                bail!(
+                    "Invalid default_hours {}: must be a non-negative number",
+                    hours
+                );
+            }
+        }
+        if let Some(timezone) = &self.timezone {
+            if timezone.parse::<Tz>().is_err() {
+                bail!(
+                    "Invalid timezone `{}`: must be an IANA name such as Europe/Rome",
+                    timezone
+                );
+            }
+        }
+        if let Some(template) = &self.name_template {
+            crate::name_template::validate(template)?;
+        }
+        if let Some(suffix) = &self.user_agent_suffix {
+            if !suffix.chars().all(|c| c == ' ' || c.is_ascii_graphic()) {
+                bail!(
+                    "Invalid user_agent_suffix `{}`: only printable ASCII characters can be sent in a header",
+                    suffix
+                );
+            }
+        }
+        for (position, template) in self.templates.iter().enumerate() {
+            if self.templates[..position]
+                .iter()
+                .any(|other| other.name == template.name)
+            {
+                bail!(
+                    "Duplicate template name `{}` in [[templates]]",
+                    template.name
+                );
+            }
+            if let Some(activity) = &template.activity {
+                self.activity_value(activity)
+                    .map_err(|e| anyhow!("Invalid template `{}`: {}", template.name, e))?;
+            }
+        }
+        for (keyword, index) in &self.activities {
+            if *index < 0 {
+                bail!(
+                    "Invalid index {} for activity `{}` in [activities]: indices must be non-negative integers",
+                    index,
+                    keyword
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Hours per day above which `add` warns, 24 unless configured.
+    pub fn max_daily_hours(&self) -> f64 {
+        self.max_daily_hours.unwrap_or(DEFAULT_MAX_DAILY_HOURS)
+    }
+
+    /// The `[[templates]]` entry with this name.
+    pub fn template(&self, name: &str) -> Option<&EntryTemplate> {
+        self.templates.iter().find(|template| template.name == name)
+    }
+
+    /// Hours logged by `add` when none are given, 8 unless configured.
+    pub fn default_hours(&self) -> f64 {
+        self.default_hours.unwrap_or(DEFAULT_HOURS)
+    }
+
+    /// Today's date in the configured timezone, or in the local one when none is set.
+    pub fn today(&self) -> NaiveDate {
+        match self
+            .timezone
+            .as_deref()
+            .and_then(|name| name.parse::<Tz>().ok())
+        {
+            Some(timezone) => Utc::now().with_timezone(&timezone).date_naive(),
+            None => Local::now().date_naive(),
+        }
+    }
+
+    /// The timezone `today()` uses, for messages.
+    pub fn timezone_name(&self) -> &str {
+        self.timezone.as_deref().unwrap_or("local time")
+    }
+
+    /// The User-Agent sent with every request, with the configured suffix if any.
+    pub fn user_agent(&self) -> String {
+        match self.user_agent_suffix.as_deref().map(str::trim) {
+            Some(suffix) if !suffix.is_empty() => {
+                format!("{} {}", crate::api::USER_AGENT, suffix)
+            }
+            _ => crate::api::USER_AGENT.to_string(),
+        }
+    }
+
+    /// The resolved API key; only meaningful after `resolve` has succeeded.
+    pub fn api_key(&self) -> &str {
+        self.api_key.as_deref().unwrap_or_default()
+    }
+
+    /// Replace every occurrence of the API key in `text` with `***`, for anything that gets
+    /// printed or logged.
+    pub fn redact(&self, text: &str) -> String {
+        match self.api_key.as_deref() {
+            Some(api_key) if !api_key.is_empty() => text.replace(api_key, "***"),
+            _ => text.to_string(),
+        }
+    }
+
+    /// Map an activity keyword, or a bare status index, to the index of the matching status label.
+    ///
+    /// Keywords from the config's `[activities]` table take precedence over the built-in ones.
+    pub fn activity_index(&self, activity: &str) -> Option<i64> {
+        if let Ok(index) = activity.trim().parse::<i64>() {
+            return self.activity_name(index).map(|_| index);
+        }
+
+        let activity = activity.to_lowercase();
+        self.activities
+            .iter()
+            .find(|(keyword, _)| keyword.to_lowercase() == activity)
+            .map(|(_, index)| *index)
+            .or_else(|| {
+                ACTIVITIES
+                    .iter()
+                    .find(|(keyword, _)| *keyword == activity)
+                    .map(|(_, index)| *index)
+            })
+    }
+
+    /// Like `activity_index`, but failing with the list of valid keywords.
+    pub fn activity_value(&self, activity: &str) -> Result<i64, CliError> {
+        self.activity_index(activity).ok_or_else(|| {
+            CliError::InvalidInput(format!(
+                "Invalid activity type: {}, valid activity types are: {}",
+                activity,
+                self.valid_activities()
+            ))
+        })
+    }
+
+    /// The accepted activity keywords, configured ones first, for offering as choices
+    pub fn activity_keywords(&self) -> Vec<String> {
+        let mut keywords: Vec<String> = self.activities.keys().cloned().collect();
+        for (builtin, _) in ACTIVITIES {
+            if !builtin.is_empty() && !keywords.iter().any(|keyword| keyword == builtin) {
+                keywords.push(builtin.to_string());
+            }
+        }
+        keywords
+    }
+
+    /// Human-readable list of the accepted activity keywords, including configured ones
+    pub fn valid_activities(&self) -> String {
+        let configured: Vec<&str> = self
+            .activities
+            .keys()
+            .map(String::as_str)
+            .filter(|keyword| !ACTIVITIES.iter().any(|(builtin, _)| builtin == keyword))
+            .collect();
+        if configured.is_empty() {
+            VALID_ACTIVITIES.to_string()
+        } else {
+            format!("{}, {}", configured.join(", "), VALID_ACTIVITIES)
+        }
+    }
+
+    /// Reverse of `activity_index`, for displaying a status index as a keyword
+    pub fn activity_name(&self, index: i64) -> Option<String> {
+        self.activities
+            .iter()
+            .find(|(_, value)| **value == index)
+            .map(|(keyword, _)| keyword.clone())
+            .or_else(|| {
+                ACTIVITIES
+                    .iter()
+                    .find(|(_, value)| *value == index)
+                    .map(|(keyword, _)| keyword.to_string())
+            })
+    }
+}
+
+fn default_max_retries() -> u32 {
+    3
+}
+
+fn default_complexity_retries() -> u32 {
+    2
+}
+
+fn default_timeout_seconds() -> u64 {
+    30
+}
+
+fn default_cache_ttl_minutes() -> u64 {
+    60
+}
+
+fn default_api_url() -> String {
+    crate::api::MONDAY_API_URL.to_string()
+}
+
+/// Accept an ID written as a TOML integer or as a string of digits, an empty string is unset.
+fn deserialize_id<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum RawId {
+        Number(u64),
+        Text(String),
+    }
+
+    match RawId::deserialize(deserializer)? {
+        RawId::Number(id) => Ok(id),
+        RawId::Text(text) if text.trim().is_empty() => Ok(0),
+        RawId::Text(text) => parse_id(&text).map_err(de::Error::custom),
+    }
+}
+
+fn parse_id(value: &str) -> Result<u64, String> {
+    value
+        .trim()
+        .parse::<u64>()
+        .map_err(|_| format!("expected a numeric ID, got `{}`", value))
+}
+
+/// Read an environment variable, treating an empty value as unset.
+fn env_var(name: &str) -> Option<String> {
+    env::var(name).ok().filter(|value| !value.is_empty())
 }
@@ -0,0 +1,87 @@
+use crate::models::{GraphQLError, GraphQLErrorKind};
+use std::fmt;
+
+/// Failures that callers may want to tell apart, each mapped to its own exit code.
+///
+/// Anything else bubbles up as a plain `anyhow::Error` and exits with 1.
+#[derive(Debug)]
+pub enum CliError {
+    /// A command line value or input file failed local validation
+    InvalidInput(String),
+    /// No group on the board has the requested title
+    GroupNotFound(String),
+    /// The API returned no board for the configured ID, without saying why
+    BoardNotFound(u64),
+    /// No board visible to the API key has the configured `board_name`
+    BoardNameNotFound(String),
+    /// The API answered with GraphQL errors
+    GraphQL(Vec<GraphQLError>),
+}
+
+impl CliError {
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            CliError::InvalidInput(_) => 3,
+            CliError::GroupNotFound(_) => 4,
+            CliError::BoardNotFound(_) | CliError::BoardNameNotFound(_) => 6,
+            CliError::GraphQL(_) => 5,
+        }
+    }
+
+    /// The category of the first classifiable GraphQL error, if this is one.
+    pub fn graphql_kind(&self) -> Option<GraphQLErrorKind> {
+        let CliError::GraphQL(errors) = self else {
+            return None;
+        };
+        let kind = errors
+            .iter()
+            .map(GraphQLError::kind)
+            .find(|kind| *kind != GraphQLErrorKind::Other);
+        Some(kind.unwrap_or(GraphQLErrorKind::Other))
+    }
+
+    /// A suggestion on how to fix the error, where there is an obvious one.
+    pub fn hint(&self) -> Option<&'static str> {
+        match self {
+            CliError::BoardNotFound(_) => {
+                Some("Check board_id in the config, it's the number in the board's URL")
+            }
+            CliError::BoardNameNotFound(_) => {
+                Some("Check board_name in the config, it has to match the board's name exactly")
+            }
+            _ => self.graphql_kind().and_then(GraphQLErrorKind::hint),
+        }
+    }
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::InvalidInput(message) | CliError::GroupNotFound(message) => {
+                write!(f, "{}", message)
+            }
+            CliError::BoardNotFound(board_id) => write!(
+                f,
+                "Board {} was not found, either the ID is wrong or the API key's user can't see it",
+                board_id
+            ),
+            CliError::BoardNameNotFound(name) => write!(
+                f,
+                "No board named {} was found, either the name is wrong or the API key's user can't see it",
+                name
+            ),
+            CliError::GraphQL(errors) => {
+                let messages: Vec<String> = errors
+                    .iter()
+                    .map(|error| match error.code() {
+                        Some(code) => format!("{} ({})", error.message, code),
+                        None => error.message.clone(),
+                    })
+                    .collect();
+                write!(f, "GraphQL Error: {}", messages.join("; "))
+            }
+        }
+    }
+}
+
+impl std::error::Error for CliError {}
@@ -1,19 +1,169 @@
+mod api;
+mod audit;
+mod cache;
 mod config;
+mod error;
 mod models;
+mod name_template;
+mod output;
+mod progress;
+mod prompt;
+mod secrets;
+mod state;
+mod xlsx;
 
-use anyhow::Result;
-use clap::{Arg, Command};
-use config::Config;
+use anyhow::{anyhow, Result};
+use api::{group_by_title, MondayClient, MAX_PAGE_LIMIT};
+use chrono::{Datelike, Days, NaiveDate, Weekday};
+use clap::parser::ValueSource;
+use clap::{Arg, ArgAction, ArgGroup, ArgMatches, Command};
+use clap_complete::Shell;
+use config::{ColumnTransform, Config};
+use env_logger::WriteStyle;
+use error::CliError;
+use futures::stream::{self, StreamExt};
+use log::{info, warn, LevelFilter};
 use models::*;
 use prettytable::{Table, row};
-use reqwest::Client;
+use progress::Progress;
+use rustyline::error::ReadlineError;
 use serde_json::json;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
 
-const MONDAY_API_URL: &str = "https://api.monday.com/v2";
+// Accepted by `parse_date`, the separator tells them apart so none is ambiguous
+const DATE_FORMATS: [(&str, &str); 3] = [
+    ("%Y-%m-%d", "YYYY-MM-DD"),
+    ("%m/%d/%Y", "MM/DD/YYYY"),
+    ("%d.%m.%Y", "DD.MM.YYYY"),
+];
+// The columns every `query --csv` export starts with, before the board's
+const CSV_FIXED_HEADER: [&str; 3] = ["ID", "Name", "Group"];
+// Shown by `query --show-null` for columns without a value, as opposed to an empty one
+const NULL_MARKER: &str = "—";
 
 #[tokio::main]
-async fn main() -> Result<()> {
-    let matches = Command::new("monday-claim")
+async fn main() -> ExitCode {
+    // Set up by `run` once the config is loaded, so the error can be redacted with it
+    let mut api = None;
+    match run(&mut api).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => ExitCode::from(report_error(&e, api.as_ref().map(MondayClient::config))),
+    }
+}
+
+/// Print the error that ended a command with a hint where there is one, returning the exit
+/// code it calls for.
+///
+/// With a config the API key is redacted, e.g. from a GraphQL error message quoting it.
+fn report_error(e: &anyhow::Error, config: Option<&Config>) -> u8 {
+    let message = format!("{:#}", e);
+    output::error(match config {
+        Some(config) => config.redact(&message),
+        None => message,
+    });
+    let cli_error = e.downcast_ref::<CliError>();
+    let hint = cli_error
+        .and_then(CliError::hint)
+        .or_else(|| api::http_status_hint(e));
+    if let Some(hint) = hint {
+        output::hint(hint);
+    }
+    cli_error.map_or(1, CliError::exit_code)
+}
+
+/// The `--format` option of the read commands, see `Format`.
+fn format_arg() -> Arg {
+    Arg::new("format")
+        .long("format")
+        .value_name("FORMAT")
+        .help("Print the results as tables, JSON, CSV or a Markdown table")
+        .value_parser(["table", "json", "csv", "markdown"])
+        .default_value("table")
+}
+
+/// `--json` from before `--format`, kept working for existing scripts.
+fn json_alias() -> Arg {
+    Arg::new("json")
+        .long("json")
+        .help("Same as --format json")
+        .action(ArgAction::SetTrue)
+        .conflicts_with("format")
+        .hide(true)
+}
+
+/// `--name` of the commands that create items.
+fn name_arg() -> Arg {
+    Arg::new("name")
+        .short('n')
+        .long("name")
+        .value_name("NAME")
+        .help("Item name (generated from the name template when omitted)")
+}
+
+/// `--name-template`, checked while parsing so a bad placeholder fails before any request.
+fn name_template_arg(help: &'static str) -> Arg {
+    Arg::new("name-template")
+        .long("name-template")
+        .value_name("TEMPLATE")
+        .help(help)
+        .value_parser(|template: &str| {
+            name_template::validate(template)
+                .map(|_| template.to_string())
+                .map_err(|e| e.to_string())
+        })
+}
+
+fn activity_arg(help: &'static str) -> Arg {
+    Arg::new("activity")
+        .short('a')
+        .long("activity")
+        .value_name("ACTIVITY")
+        .help(help)
+}
+
+fn client_arg() -> Arg {
+    Arg::new("client")
+        .short('c')
+        .long("client")
+        .value_name("CLIENT")
+        .help("Client name")
+}
+
+fn wi_arg() -> Arg {
+    Arg::new("wi")
+        .short('w')
+        .long("wi")
+        .value_name("WORK_ITEM")
+        .help("Work item code")
+}
+
+fn hours_arg(help: &'static str) -> Arg {
+    Arg::new("hours")
+        .short('H')
+        .long("hours")
+        .value_name("HOURS")
+        .help(help)
+        // Let negative values through so they get a proper validation error
+        .allow_negative_numbers(true)
+}
+
+fn create_group_arg() -> Arg {
+    Arg::new("create-group")
+        .long("create-group")
+        .help("Create the year group if the board doesn't have it yet")
+        .action(ArgAction::SetTrue)
+}
+
+/// Every subcommand and option, shared by argument parsing and `completions`.
+fn build_cli() -> Command {
+    Command::new("monday-claim")
         .version("1.0")
         .author("Valerio Graziani")
         .about("CLI tool for managing Monday.com board items")
@@ -22,17 +172,296 @@ async fn main() -> Result<()> {
                 .short('C')
                 .long("config")
                 .value_name("FILE")
-                .help("Path to config file")
-                .required(true),
-        )
-        .subcommand(Command::new("query").about("Query board items").arg(
-            Arg::new("limit")
-                .short('l')
-                .long("limit")
-                .value_name("LIMIT")
-                .help("Number of items to fetch (default: 10)")
-                .default_value("10"),
-        ))
+                .help("Path to config file (default: $MONDAY_CLAIM_CONFIG, ./monday-claim.toml, then the user config directory)"),
+        )
+        .arg(
+            Arg::new("keyring")
+                .long("keyring")
+                .help("Read the API key stored by `login` from the keychain, as api_key = \"keyring\" does")
+                .action(ArgAction::SetTrue)
+                .global(true),
+        )
+        .arg(
+            Arg::new("verbose")
+                .short('v')
+                .long("verbose")
+                .help("Show progress messages, repeat for more detail (-vv for debug output)")
+                .action(ArgAction::Count)
+                .global(true),
+        )
+        .arg(
+            Arg::new("dry-run")
+                .long("dry-run")
+                .help("Print mutations instead of sending them to the API")
+                .action(ArgAction::SetTrue)
+                .global(true),
+        )
+        .arg(
+            Arg::new("quiet")
+                .short('q')
+                .long("quiet")
+                .help("Don't show progress bars while fetching pages or creating items")
+                .action(ArgAction::SetTrue)
+                .global(true),
+        )
+        .arg(
+            Arg::new("no-color")
+                .long("no-color")
+                .help("Disable colored output (also disabled by setting NO_COLOR)")
+                .action(ArgAction::SetTrue)
+                .global(true),
+        )
+        .arg(
+            Arg::new("timeout")
+                .long("timeout")
+                .value_name("SECONDS")
+                .help("Seconds to wait for each API request (default: 30)")
+                .value_parser(clap::value_parser!(u64).range(1..))
+                .global(true),
+        )
+        .arg(
+            Arg::new("board-name")
+                .long("board-name")
+                .value_name("NAME")
+                .help("Use the board with this name instead of the configured board_id")
+                .global(true),
+        )
+        .arg(
+            Arg::new("board-id")
+                .long("board-id")
+                .value_name("ID")
+                .help("Use the board with this ID instead of the configured one")
+                .value_parser(clap::value_parser!(u64).range(1..))
+                .conflicts_with("board-name")
+                .global(true),
+        )
+        .arg(
+            Arg::new("api-url")
+                .long("api-url")
+                .value_name("URL")
+                .help("GraphQL endpoint to send requests to (default: https://api.monday.com/v2)")
+                .global(true),
+        )
+        .arg(
+            Arg::new("yes")
+                .long("yes")
+                .help("DANGEROUS: answer yes to every confirmation, e.g. before replacing files")
+                .action(ArgAction::SetTrue)
+                .global(true),
+        )
+        .arg(
+            Arg::new("strict")
+                .long("strict")
+                .help("Check that every configured column ID exists on the board before running the command")
+                .action(ArgAction::SetTrue)
+                .global(true),
+        )
+        .arg(
+            Arg::new("refresh-cache")
+                .long("refresh-cache")
+                .help("Fetch the board's groups again even if the cached ones are still fresh")
+                .action(ArgAction::SetTrue)
+                .global(true),
+        )
+        .arg(
+            Arg::new("audit-reads")
+                .long("audit-reads")
+                .help("Also record the queries sent in the audit_log file, not only the changes")
+                .action(ArgAction::SetTrue)
+                .global(true),
+        )
+        .arg(
+            Arg::new("max-retries")
+                .long("max-retries")
+                .value_name("COUNT")
+                .help("Retries for network errors and 5xx responses (default: 3)")
+                .value_parser(clap::value_parser!(u32))
+                .global(true),
+        )
+        .subcommand(
+            Command::new("query")
+                .about("Query board items")
+                .arg(
+                    Arg::new("limit")
+                        .short('l')
+                        .long("limit")
+                        .value_name("LIMIT")
+                        .help("Number of items to fetch per page, 1 to 500 (default: 10)")
+                        .value_parser(clap::value_parser!(u32).range(1..))
+                        .default_value("10"),
+                )
+                .arg(
+                    Arg::new("all")
+                        .long("all")
+                        .help("Follow pagination cursors until every item has been fetched")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("year")
+                        .short('y')
+                        .long("year")
+                        .value_name("YEAR")
+                        .help("Only show items in the group with this title (e.g., 2024)"),
+                )
+                .arg(
+                    Arg::new("cursor")
+                        .long("cursor")
+                        .value_name("CURSOR")
+                        .help("Start from the page after this cursor, as printed by --show-cursor"),
+                )
+                .arg(
+                    Arg::new("show-cursor")
+                        .long("show-cursor")
+                        .help("Print the cursor of the next page to stderr after the results")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("mine")
+                        .long("mine")
+                        .help("Only show items whose person column includes user_id")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("include-archived")
+                        .long("include-archived")
+                        .help("Also list archived items, marked with (archived) after their name")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("with-subitems")
+                        .long("with-subitems")
+                        .help("Also fetch subitems and show them under their parent in the table")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("fields")
+                        .long("fields")
+                        .value_name("LIST")
+                        .help("Only fetch these columns' values, comma-separated IDs or [columns] names, to lower the API cost"),
+                )
+                .arg(
+                    Arg::new("since-last")
+                        .long("since-last")
+                        .help("Only show items created since the last query with --since-last, fetching every page")
+                        .action(ArgAction::SetTrue)
+                        .conflicts_with("cursor"),
+                )
+                .arg(
+                    Arg::new("reset-marker")
+                        .long("reset-marker")
+                        .help("Forget which items --since-last has already shown")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("offline")
+                        .long("offline")
+                        .help("Show the items of the last query from the cache, without contacting Monday.com")
+                        .action(ArgAction::SetTrue)
+                        .conflicts_with_all([
+                            "all",
+                            "cursor",
+                            "show-cursor",
+                            "include-archived",
+                            "with-subitems",
+                            "fields",
+                            "since-last",
+                            "reset-marker",
+                        ]),
+                )
+                .arg(
+                    Arg::new("from")
+                        .long("from")
+                        .value_name("DATE")
+                        .help("Only show items dated on or after this day (YYYY-MM-DD)"),
+                )
+                .arg(
+                    Arg::new("to")
+                        .long("to")
+                        .value_name("DATE")
+                        .help("Only show items dated on or before this day (YYYY-MM-DD)"),
+                )
+                .arg(
+                    Arg::new("max-col-width")
+                        .long("max-col-width")
+                        .value_name("N")
+                        .help("Truncate table cells longer than N characters")
+                        .value_parser(clap::value_parser!(u16).range(2..)),
+                )
+                .arg(
+                    Arg::new("columns")
+                        .long("columns")
+                        .value_name("LIST")
+                        .help("Only show these columns, comma-separated IDs or [columns] names (e.g., date,client,hours)"),
+                )
+                .arg(
+                    Arg::new("group-order")
+                        .long("group-order")
+                        .value_name("ORDER")
+                        .help("Order groups by title, years numerically and other titles after them, or keep the API's order")
+                        .value_parser(["asc", "desc", "api"])
+                        .default_value("api"),
+                )
+                .arg(
+                    Arg::new("show-null")
+                        .long("show-null")
+                        .help("Show columns that are unset as — instead of blank, so they stand out from ones set to an empty value (table and Markdown formats)")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("sort")
+                        .long("sort")
+                        .value_name("FIELD")
+                        .help("Order items by date, hours or name (items without a value go last)")
+                        .value_parser(["date", "hours", "name"]),
+                )
+                .arg(
+                    Arg::new("desc")
+                        .long("desc")
+                        .help("Sort in descending order")
+                        .action(ArgAction::SetTrue)
+                        .requires("sort"),
+                )
+                .arg(format_arg())
+                .arg(json_alias().conflicts_with("csv"))
+                .arg(
+                    Arg::new("csv")
+                        .long("csv")
+                        .value_name("PATH")
+                        .help("Same as --format csv --output PATH")
+                        .conflicts_with("format")
+                        .hide(true),
+                )
+                .arg(
+                    Arg::new("output")
+                        .short('o')
+                        .long("output")
+                        .value_name("PATH")
+                        .help("Write the output to a file instead of stdout"),
+                )
+                .group(ArgGroup::new("file").args(["csv", "output"]))
+                .arg(
+                    Arg::new("overwrite")
+                        .long("overwrite")
+                        .help("Overwrite the output file if it already exists")
+                        .action(ArgAction::SetTrue)
+                        .requires("file"),
+                )
+                .arg(
+                    Arg::new("append")
+                        .long("append")
+                        .help("With --format csv, add the items to the end of the output file, lined up with its header")
+                        .action(ArgAction::SetTrue)
+                        .requires("file")
+                        .conflicts_with("overwrite"),
+                )
+                .arg(
+                    Arg::new("dedup")
+                        .long("dedup")
+                        .help("With --append, leave out items whose ID is already in the file")
+                        .action(ArgAction::SetTrue)
+                        .requires("append"),
+                ),
+        )
         .subcommand(
             Command::new("add")
                 .about("Add a new item to the board")
@@ -42,22 +471,184 @@ async fn main() -> Result<()> {
                         .long("year")
                         .value_name("YEAR")
                         .help("Year for the group (e.g., 2024)")
-                        .required(true),
+                        .required_unless_present("interactive"),
                 )
+                .arg(name_arg())
+                .arg(name_template_arg("Name to use when --name is omitted, e.g. \"{date} - {client} - {wi}\" (placeholders: year, activity, date, client, wi, hours)"))
                 .arg(
-                    Arg::new("name")
-                        .short('n')
-                        .long("name")
+                    activity_arg("Activity type: vacation, billable, holding, education, work_reduction, tbd, holiday, illness, or its status index 0-8")
+                        .required_unless_present_any(["interactive", "use-last", "template"]),
+                )
+                .arg(
+                    Arg::new("date")
+                        .short('d')
+                        .long("date")
+                        .value_name("DATE")
+                        .help("Date as YYYY-MM-DD, MM/DD/YYYY or DD.MM.YYYY (default: today in the configured timezone)"),
+                )
+                .arg(
+                    client_arg()
+                        .required_unless_present_any(["interactive", "use-last", "template"]),
+                )
+                .arg(
+                    wi_arg().required_unless_present_any(["interactive", "use-last", "template"]),
+                )
+                .arg(hours_arg("Number of hours, fractions allowed (e.g., 7.5); defaults to default_hours from the config, or 8"))
+                .arg(
+                    Arg::new("interactive")
+                        .short('i')
+                        .long("interactive")
+                        .help("Prompt for every field not given on the command line")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("allow-duplicate")
+                        .long("allow-duplicate")
+                        .help("Create the item even if the group already has one with the same name, date and client")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("template")
+                        .short('t')
+                        .long("template")
                         .value_name("NAME")
-                        .help("Item name")
+                        .help("Fill in the activity, client and work item not given from this [[templates]] entry"),
+                )
+                .arg(
+                    Arg::new("use-last")
+                        .long("use-last")
+                        .help("Use the client, work item and activity of the last add on this board for any not given")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(create_group_arg())
+                .arg(
+                    Arg::new("json")
+                        .long("json")
+                        .help("Print the created item, or the error, as JSON and status messages to stderr")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("set")
+                        .long("set")
+                        .value_name("COLUMN_ID=JSON")
+                        .help("Also set this column to a raw JSON value, replacing any value generated for it, e.g. link__1='{\"url\":\"https://example.com\",\"text\":\"Docs\"}' (repeatable)")
+                        .action(ArgAction::Append),
+                )
+                .arg(
+                    Arg::new("column-map")
+                        .long("column-map")
+                        .value_name("SETTING=COLUMN_ID,...")
+                        .help("Use these column IDs instead of the [columns] config for this run, e.g. client=text5,hours=numbers2"),
+                )
+                .arg(
+                    Arg::new("verify")
+                        .long("verify")
+                        .help("Read the created item back and warn unless its person column includes user_id")
+                        .action(ArgAction::SetTrue),
+                ),
+        )
+        .subcommand(
+            Command::new("log-week")
+                .about("Add the same entry for every weekday of a week")
+                .arg(
+                    Arg::new("start")
+                        .short('s')
+                        .long("start")
+                        .value_name("DATE")
+                        .help("Monday of the week, as YYYY-MM-DD, MM/DD/YYYY or DD.MM.YYYY")
+                        .required(true),
+                )
+                .arg(name_arg())
+                .arg(name_template_arg("Name to use when --name is omitted, rendered for each day"))
+                .arg(
+                    activity_arg("Activity type, see `activities` for the accepted keywords")
+                        .required(true),
+                )
+                .arg(client_arg().required(true))
+                .arg(wi_arg().required(true))
+                .arg(
+                    hours_arg("Number of hours per day, fractions allowed (e.g., 7.5)")
+                        .required(true),
+                )
+                .arg(
+                    Arg::new("include-weekends")
+                        .long("include-weekends")
+                        .help("Also add entries for Saturday and Sunday")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(create_group_arg()),
+        )
+        .subcommand(
+            Command::new("bulk-add")
+                .about("Add items from a CSV file")
+                .arg(
+                    Arg::new("file")
+                        .short('f')
+                        .long("file")
+                        .value_name("CSV")
+                        .help("CSV file with columns: year, name, activity, date, client, wi, hours")
+                        .required(true),
+                )
+                .arg(
+                    Arg::new("skip-invalid")
+                        .long("skip-invalid")
+                        .help("Create the valid rows even if some rows fail validation")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("concurrency")
+                        .long("concurrency")
+                        .value_name("N")
+                        .help("Number of items created at the same time")
+                        .value_parser(clap::value_parser!(u32).range(1..))
+                        .default_value("1"),
+                ),
+        )
+        .subcommand(
+            Command::new("get")
+                .about("Show the details of a single item")
+                .arg(
+                    Arg::new("id")
+                        .long("id")
+                        .value_name("ITEM_ID")
+                        .help("ID of the item to show")
+                        .required(true),
+                )
+                .arg(
+                    Arg::new("fields")
+                        .long("fields")
+                        .value_name("LIST")
+                        .help("Only fetch these columns' values, comma-separated IDs or [columns] names"),
+                )
+                .arg(format_arg()),
+        )
+        .subcommand(
+            Command::new("move")
+                .about("Move an item to the group of another year")
+                .arg(
+                    Arg::new("id")
+                        .long("id")
+                        .value_name("ITEM_ID")
+                        .help("ID of the item to move")
                         .required(true),
                 )
                 .arg(
-                    Arg::new("activity")
-                        .short('a')
-                        .long("activity")
-                        .value_name("ACTIVITY")
-                        .help("Activity type: vacation, billable, holding, education, work_reduction, tbd, holiday, illness")
+                    Arg::new("year")
+                        .short('y')
+                        .long("year")
+                        .value_name("YEAR")
+                        .help("Year of the target group (e.g., 2024)")
+                        .required(true),
+                ),
+        )
+        .subcommand(
+            Command::new("duplicate")
+                .about("Create a copy of an existing item")
+                .arg(
+                    Arg::new("id")
+                        .long("id")
+                        .value_name("ITEM_ID")
+                        .help("ID of the item to duplicate")
                         .required(true),
                 )
                 .arg(
@@ -65,552 +656,3506 @@ async fn main() -> Result<()> {
                         .short('d')
                         .long("date")
                         .value_name("DATE")
-                        .help("Date in YYYY-MM-DD format")
+                        .help("Date of the copy as YYYY-MM-DD, MM/DD/YYYY or DD.MM.YYYY (defaults to the original's date)"),
+                ),
+        )
+        .subcommand(
+            Command::new("delete")
+                .about("Delete one or more items")
+                .arg(
+                    Arg::new("id")
+                        .long("id")
+                        .value_name("ITEM_ID")
+                        .help("ID of an item to delete, repeatable or comma-separated (e.g., --id 1,2,3)")
+                        .required(true)
+                        .value_delimiter(',')
+                        .action(ArgAction::Append),
+                )
+                .arg(
+                    Arg::new("force")
+                        .long("force")
+                        .help("Delete without asking for confirmation")
+                        .action(ArgAction::SetTrue),
+                ),
+        )
+        .subcommand(
+            Command::new("update")
+                .about("Change an existing item")
+                .arg(
+                    Arg::new("id")
+                        .long("id")
+                        .value_name("ITEM_ID")
+                        .help("ID of the item to update")
+                        .required(true),
+                )
+                .arg(
+                    Arg::new("name")
+                        .short('n')
+                        .long("name")
+                        .value_name("NAME")
+                        .help("New name of the item"),
+                )
+                .arg(
+                    Arg::new("set")
+                        .long("set")
+                        .value_name("COLUMN_ID=JSON")
+                        .help("Set this column to a raw JSON value, e.g. link__1='{\"url\":\"https://example.com\",\"text\":\"Docs\"}' (repeatable)")
+                        .action(ArgAction::Append),
+                )
+                .group(
+                    ArgGroup::new("changes")
+                        .args(["name", "set"])
+                        .required(true)
+                        .multiple(true),
+                ),
+        )
+        .subcommand(
+            Command::new("update-hours")
+                .about("Change the hours of an existing item")
+                .arg(
+                    Arg::new("id")
+                        .long("id")
+                        .value_name("ITEM_ID")
+                        .help("ID of the item to update")
+                        .required(true),
+                )
+                .arg(
+                    hours_arg("New number of hours, fractions allowed (e.g., 7.5)")
+                        .required(true),
+                ),
+        )
+        .subcommand(
+            Command::new("summary")
+                .about("Total the hours logged per client")
+                .arg(
+                    Arg::new("year")
+                        .short('y')
+                        .long("year")
+                        .value_name("YEAR")
+                        .help("Only count items in the group with this title (e.g., 2024)"),
+                )
+                .arg(format_arg())
+                .arg(json_alias()),
+        )
+        .subcommand(
+            Command::new("stats")
+                .about("Break the hours logged down by activity")
+                .arg(
+                    Arg::new("year")
+                        .short('y')
+                        .long("year")
+                        .value_name("YEAR")
+                        .help("Only count items in the group with this title (e.g., 2024)"),
+                )
+                .arg(
+                    Arg::new("mine")
+                        .long("mine")
+                        .help("Only count items whose person column includes user_id")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(format_arg())
+                .arg(json_alias()),
+        )
+        .subcommand(
+            Command::new("export")
+                .about("Export every item to an Excel workbook with a sheet per year group")
+                .arg(
+                    Arg::new("path")
+                        .value_name("PATH")
+                        .help("The .xlsx file to write")
                         .required(true),
                 )
+                .arg(
+                    Arg::new("year")
+                        .short('y')
+                        .long("year")
+                        .value_name("YEAR")
+                        .help("Only export the group with this title (e.g., 2024)"),
+                )
+                .arg(
+                    Arg::new("overwrite")
+                        .long("overwrite")
+                        .help("Overwrite the file if it already exists")
+                        .action(ArgAction::SetTrue),
+                ),
+        )
+        .subcommand(
+            Command::new("recent")
+                .about("Show your most recent entries, newest first")
+                .arg(
+                    Arg::new("count")
+                        .short('n')
+                        .long("count")
+                        .value_name("N")
+                        .help("Number of entries to show")
+                        .value_parser(clap::value_parser!(u32).range(1..))
+                        .default_value("5"),
+                ),
+        )
+        .subcommand(
+            Command::new("search")
+                .about("Find items across all groups whose text contains the given values")
                 .arg(
                     Arg::new("client")
                         .short('c')
                         .long("client")
-                        .value_name("CLIENT")
-                        .help("Client name")
-                        .required(true),
+                        .value_name("TEXT")
+                        .help("Only items whose client contains this text"),
                 )
                 .arg(
                     Arg::new("wi")
                         .short('w')
                         .long("wi")
-                        .value_name("WORK_ITEM")
-                        .help("Work item code")
-                        .required(true),
+                        .value_name("TEXT")
+                        .help("Only items whose work item contains this text"),
                 )
                 .arg(
-                    Arg::new("hours")
-                        .short('H')
-                        .long("hours")
-                        .value_name("HOURS")
-                        .help("Number of hours")
-                        .required(true),
-                ),
-        )
-        .get_matches();
+                    Arg::new("name")
+                        .short('n')
+                        .long("name")
+                        .value_name("TEXT")
+                        .help("Only items whose name contains this text"),
+                )
+                .group(
+                    ArgGroup::new("filters")
+                        .args(["client", "wi", "name"])
+                        .required(true)
+                        .multiple(true),
+                ),
+        )
+        .subcommand(
+            Command::new("groups")
+                .about("List the board's groups without fetching items")
+                .arg(
+                    Arg::new("json")
+                        .long("json")
+                        .help("Print groups as JSON instead of a table")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("group-order")
+                        .long("group-order")
+                        .value_name("ORDER")
+                        .help("Order groups by title, years numerically and other titles after them, or keep the API's order")
+                        .value_parser(["asc", "desc", "api"])
+                        .default_value("api"),
+                ),
+        )
+        .subcommand(
+            Command::new("activities")
+                .about("List the accepted activity keywords and their status indices"),
+        )
+        .subcommand(
+            Command::new("columns").about("List the board's column IDs, titles and types"),
+        )
+        .subcommand(
+            Command::new("init")
+                .about("Write a commented config file to fill in")
+                .arg(
+                    Arg::new("path")
+                        .value_name("PATH")
+                        .help("Where to write the config (default: config.toml in the user config directory)"),
+                )
+                .arg(
+                    Arg::new("force")
+                        .long("force")
+                        .help("Overwrite the file if it already exists")
+                        .action(ArgAction::SetTrue),
+                ),
+        )
+        .subcommand(
+            Command::new("login").about("Store the API key in the keychain, asking for it on the terminal or reading it from stdin"),
+        )
+        .subcommand(Command::new("logout").about("Remove the API key stored by `login` from the keychain"))
+        .subcommand(
+            Command::new("cache")
+                .about("Manage the on-disk cache of board groups, items and board IDs")
+                .subcommand_required(true)
+                .subcommand(Command::new("clear").about("Remove all cached data: groups, items and the IDs of boards given by name")),
+        )
+        .subcommand(
+            Command::new("check")
+                .visible_alias("validate")
+                .about("Check the config, API key and board access"),
+        )
+        .subcommand(
+            Command::new("ping")
+                .about("Measure the round-trip time of the API and check the API key")
+                .arg(
+                    Arg::new("count")
+                        .short('n')
+                        .long("count")
+                        .value_name("N")
+                        .help("Number of requests to send (default: 3)")
+                        .value_parser(clap::value_parser!(u32).range(1..))
+                        .default_value("3"),
+                ),
+        )
+        .subcommand(
+            Command::new("repl").about(
+                "Run subcommands line by line with the config loaded and the client set up once",
+            ),
+        )
+        .subcommand(
+            Command::new("completions")
+                .about("Print a shell completion script to stdout")
+                .arg(
+                    Arg::new("shell")
+                        .value_name("SHELL")
+                        .help("Shell to complete in")
+                        .value_parser(clap::value_parser!(Shell))
+                        .required(true),
+                ),
+        )
+}
+
+async fn run(api: &mut Option<MondayClient>) -> Result<()> {
+    let matches = build_cli().get_matches();
+
+    // Errors and warnings by default, -v raises only our own verbosity so dependencies stay
+    // quiet; RUST_LOG still takes precedence when set
+    let log_level = match matches.get_count("verbose") {
+        0 => LevelFilter::Warn,
+        1 => LevelFilter::Info,
+        2 => LevelFilter::Debug,
+        _ => LevelFilter::Trace,
+    };
+    let no_color = matches.get_flag("no-color") || env::var_os("NO_COLOR").is_some();
+    if no_color {
+        output::disable_colors();
+    }
+    if matches.get_flag("quiet") {
+        progress::disable();
+    }
+    if matches.get_flag("yes") {
+        prompt::assume_yes();
+    }
+    env_logger::Builder::new()
+        .filter_level(LevelFilter::Warn)
+        .filter_module(env!("CARGO_CRATE_NAME"), log_level)
+        .parse_default_env()
+        .write_style(if no_color {
+            WriteStyle::Never
+        } else {
+            WriteStyle::Auto
+        })
+        .format_timestamp(None)
+        .format_target(false)
+        .init();
+
+    if let Some(("completions", completions_matches)) = matches.subcommand() {
+        let shell = *completions_matches.get_one::<Shell>("shell").unwrap();
+        let mut command = build_cli();
+        let name = command.get_name().to_string();
+        // Buffered, since writing straight to stdout panics when the pipe is closed early
+        let mut script = Vec::new();
+        clap_complete::generate(shell, &mut command, name, &mut script);
+        io::stdout().write_all(&script)?;
+        return Ok(());
+    }
+
+    // Clearing the cache doesn't need a config, so it works even when the config is broken
+    if let Some(("cache", cache_matches)) = matches.subcommand() {
+        if let Some(("clear", _)) = cache_matches.subcommand() {
+            let removed = cache::clear()?;
+            output::success(format!("Removed {} cache file(s)", removed));
+        }
+        return Ok(());
+    }
+
+    // There's no config yet when creating one
+    if let Some(("init", init_matches)) = matches.subcommand() {
+        return init_config(
+            init_matches.get_one::<String>("path").map(PathBuf::from),
+            init_matches.get_flag("force"),
+        );
+    }
+
+    // The keychain is independent of the config, which may only become usable after `login`
+    match matches.subcommand_name() {
+        Some("login") => return login(),
+        Some("logout") => return logout(),
+        _ => {}
+    }
+
+    let config_path = match matches.get_one::<String>("config") {
+        Some(path) => PathBuf::from(path),
+        None => {
+            let path = Config::find_default_path()?;
+            info!("Using config file: {}", path.display());
+            path
+        }
+    };
+    let mut config = match Config::from_file(
+        &config_path,
+        matches.get_flag("keyring"),
+        matches.get_one::<u64>("board-id").copied(),
+    ) {
+        Ok(config) => config,
+        Err(e) if matches.subcommand_name() == Some("check") => {
+            output::failure(format!("Config parsed: {}", e));
+            return Err(anyhow!("Configuration check failed"));
+        }
+        Err(e) => return Err(e),
+    };
+    if let Some(max_retries) = matches.get_one::<u32>("max-retries") {
+        config.max_retries = *max_retries;
+    }
+    if let Some(timeout) = matches.get_one::<u64>("timeout") {
+        config.timeout_seconds = *timeout;
+    }
+    if let Some(api_url) = matches.get_one::<String>("api-url") {
+        config.api_url = api_url.clone();
+        // The flag skipped the check the config file went through
+        config.validate()?;
+    }
+
+    if let Some(board_name) = matches.get_one::<String>("board-name") {
+        config.board_name = Some(board_name.clone());
+        config.board_id = 0;
+    }
+    if matches.get_flag("strict") {
+        config.strict = true;
+    }
+    if let Some(("add", add_matches)) = matches.subcommand() {
+        if let Some(map) = add_matches.get_one::<String>("column-map") {
+            config
+                .columns
+                .override_with(map)
+                .map_err(|e| CliError::InvalidInput(format!("--column-map: {}", e)))?;
+        }
+    }
+    // Nothing may be fetched offline, not even the ID of a board given by name
+    let offline = matches
+        .subcommand()
+        .is_some_and(|(name, sub_matches)| name == "query" && sub_matches.get_flag("offline"));
+    if offline && config.board_id == 0 {
+        let name = config.board_name.clone().unwrap_or_default();
+        config.board_id = cache::board_id_for_name(&name).ok_or_else(|| {
+            anyhow!(
+                "The ID of board {} isn't cached yet, run query once without --offline",
+                name
+            )
+        })?;
+    }
+
+    let api = api.insert(MondayClient::new(
+        config,
+        matches.get_flag("dry-run"),
+        matches.get_flag("refresh-cache"),
+        matches.get_flag("audit-reads"),
+    )?);
+    api.set_command(matches.subcommand_name());
+    api.resolve_board_name().await?;
+    // `columns` is how wrong IDs get fixed, so it runs regardless
+    if api.config().strict && !offline && matches.subcommand_name() != Some("columns") {
+        verify_column_ids(api).await?;
+    }
+
+    if let Some(("repl", _)) = matches.subcommand() {
+        return repl(api).await;
+    }
+    dispatch(api, &matches).await
+}
+
+/// Read subcommands line by line, e.g. `add -n ...`, and run them with the session's client.
+///
+/// Global options apply to the whole session, so they are given to `repl` itself and rejected
+/// on a line, as are the subcommands that run without a config.
+async fn repl(api: &MondayClient) -> Result<()> {
+    let mut editor = rustyline::DefaultEditor::new()?;
+    let history = state::history_path();
+    if let Some(path) = &history {
+        // There's none before the first session
+        let _ = editor.load_history(path);
+    }
+    output::info("Type a subcommand with its options, `help` to list them or `exit` to leave");
+
+    loop {
+        let line = match editor.readline("monday-claim> ") {
+            Ok(line) => line,
+            // Ctrl-C drops the line being typed, Ctrl-D ends the session
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => break,
+            Err(e) => return Err(e.into()),
+        };
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        editor.add_history_entry(line)?;
+        if matches!(line, "exit" | "quit") {
+            break;
+        }
+
+        let Some(words) = shlex::split(line) else {
+            output::error("Unbalanced quotes, the line was not run");
+            continue;
+        };
+        let matches = match build_cli().no_binary_name(true).try_get_matches_from(words) {
+            Ok(matches) => matches,
+            Err(e) => {
+                e.print()?;
+                continue;
+            }
+        };
+        if let Err(e) = check_repl_line(&matches) {
+            report_error(&e, Some(api.config()));
+            continue;
+        }
+        api.set_command(matches.subcommand_name());
+        if let Err(e) = dispatch(api, &matches).await {
+            report_error(&e, Some(api.config()));
+        }
+        // `add --json` moves the status lines to stderr for its own output only
+        output::status_to_stdout();
+    }
+
+    if let Some(path) = &history {
+        if let Some(dir) = path.parent() {
+            let _ = fs::create_dir_all(dir);
+        }
+        if let Err(e) = editor.save_history(path) {
+            warn!(
+                "Failed to save the repl history to {}: {}",
+                path.display(),
+                e
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Reject what a line of `repl` can't change for the session that is already set up.
+fn check_repl_line(matches: &ArgMatches) -> Result<()> {
+    let command = build_cli();
+    for arg in command.get_arguments() {
+        let id = arg.get_id().as_str();
+        if matches.value_source(id) == Some(ValueSource::CommandLine) {
+            return Err(CliError::InvalidInput(format!(
+                "--{} applies to the whole session, pass it to `repl` instead",
+                arg.get_long().unwrap_or(id)
+            ))
+            .into());
+        }
+    }
+    match matches.subcommand() {
+        Some((name @ ("repl" | "init" | "login" | "logout" | "cache" | "completions"), _)) => {
+            Err(CliError::InvalidInput(format!(
+                "`{}` can't be run in the repl, run it from the shell",
+                name
+            ))
+            .into())
+        }
+        Some(("add", add_matches)) if add_matches.contains_id("column-map") => {
+            Err(CliError::InvalidInput(
+                "--column-map can't be used in the repl, it would change the session's columns"
+                    .to_string(),
+            )
+            .into())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Run a subcommand that needs the API, for the command line and for each line of `repl`.
+async fn dispatch(api: &MondayClient, matches: &ArgMatches) -> Result<()> {
+    match matches.subcommand() {
+        Some(("query", query_matches)) => {
+            let mut limit = *query_matches.get_one::<u32>("limit").unwrap();
+            if limit > MAX_PAGE_LIMIT {
+                warn!(
+                    "--limit {} is more than Monday.com allows per page, using {}",
+                    limit, MAX_PAGE_LIMIT
+                );
+                limit = MAX_PAGE_LIMIT;
+            }
+            let destination = query_matches
+                .get_one::<String>("output")
+                .or(query_matches.get_one::<String>("csv"))
+                .map(String::as_str);
+            let format = Format::from_matches(query_matches);
+            if destination.is_none() {
+                format.keep_stdout_clean();
+            }
+            let append = query_matches.get_flag("append");
+            if append && format != Format::Csv {
+                return Err(CliError::InvalidInput(
+                    "--append only adds to CSV files, pass --format csv".to_string(),
+                )
+                .into());
+            }
+            // Checked against the resolved format, so --json and --csv PATH count too
+            if query_matches.get_flag("show-null") && matches!(format, Format::Json | Format::Csv) {
+                return Err(CliError::InvalidInput(
+                    "--show-null only applies to the table and Markdown formats".to_string(),
+                )
+                .into());
+            }
+            // Check before querying so we don't waste a round trip
+            if let Some(path) = destination.filter(|_| !append) {
+                if Path::new(path).exists()
+                    && !query_matches.get_flag("overwrite")
+                    && !prompt::confirm(
+                        &format!("{} already exists, replace it?", path),
+                        "--overwrite",
+                    )?
+                {
+                    return Err(anyhow!(
+                        "{} already exists, pass --overwrite to replace it",
+                        path
+                    ));
+                }
+            }
+            let from = parse_date_bound(query_matches, "from")?;
+            let to = parse_date_bound(query_matches, "to")?;
+            if let (Some(from), Some(to)) = (from, to) {
+                if from > to {
+                    return Err(CliError::InvalidInput(format!(
+                        "--from {} is after --to {}",
+                        from, to
+                    ))
+                    .into());
+                }
+            }
+
+            let options = QueryOptions {
+                limit,
+                // The newest items can be on any page
+                fetch_all: query_matches.get_flag("all") || query_matches.get_flag("since-last"),
+                with_subitems: query_matches.get_flag("with-subitems"),
+                cursor: query_matches
+                    .get_one::<String>("cursor")
+                    .map(String::as_str),
+                show_cursor: query_matches.get_flag("show-cursor"),
+                include_archived: query_matches.get_flag("include-archived"),
+                offline: query_matches.get_flag("offline"),
+                since_last: query_matches.get_flag("since-last"),
+                reset_marker: query_matches.get_flag("reset-marker"),
+                mine: query_matches.get_flag("mine"),
+                year: query_matches.get_one::<String>("year").map(String::as_str),
+                from,
+                to,
+                sort: query_matches.get_one::<String>("sort").map(String::as_str),
+                descending: query_matches.get_flag("desc"),
+                group_order: query_matches.get_one::<String>("group-order").unwrap(),
+                max_col_width: query_matches
+                    .get_one::<u16>("max-col-width")
+                    .map(|width| usize::from(*width)),
+                show_null: query_matches.get_flag("show-null"),
+                columns: query_matches
+                    .get_one::<String>("columns")
+                    .map(String::as_str),
+                fields: query_matches
+                    .get_one::<String>("fields")
+                    .map(String::as_str),
+                format,
+                destination,
+                append,
+                dedup: query_matches.get_flag("dedup"),
+            };
+            extract_board_items(api, &options).await?;
+        }
+        Some(("add", add_matches)) => {
+            let as_json = add_matches.get_flag("json");
+            if as_json {
+                // Leaves stdout to the result alone
+                output::status_to_stderr();
+            }
+            match add_item(api, add_matches).await {
+                Ok(Some(item)) if as_json => {
+                    println!("{}", json!({ "id": item.id, "name": item.name }));
+                }
+                Err(e) if as_json => {
+                    println!("{}", error_json(&e));
+                    return Err(e);
+                }
+                result => {
+                    result?;
+                }
+            }
+        }
+        Some(("log-week", week_matches)) => {
+            let field = |name: &str| week_matches.get_one::<String>(name).cloned();
+            let start = parse_date(week_matches.get_one::<String>("start").unwrap())?;
+            let fields = prompt::AddFields {
+                // Every day goes into the group of the start date, even across New Year
+                year: Some(start.year().to_string()),
+                name: field("name"),
+                activity: field("activity"),
+                date: None,
+                client: field("client"),
+                wi: field("wi"),
+                hours: field("hours"),
+            };
+            let template = week_matches
+                .get_one::<String>("name-template")
+                .or(api.config().name_template.as_ref());
+            if fields.name.is_none() && template.is_none() {
+                return Err(missing_name_error());
+            }
+
+            log_week(
+                api,
+                start,
+                &fields,
+                template.map(String::as_str),
+                week_matches.get_flag("include-weekends"),
+                week_matches.get_flag("create-group"),
+            )
+            .await?;
+        }
+        Some(("get", get_matches)) => {
+            let item_id = get_matches.get_one::<String>("id").unwrap();
+            let fields = get_matches.get_one::<String>("fields");
+            let format = Format::from_matches(get_matches);
+            format.keep_stdout_clean();
+            show_board_item(api, item_id, fields.map(String::as_str), format).await?;
+        }
+        Some(("move", move_matches)) => {
+            let item_id = move_matches.get_one::<String>("id").unwrap();
+            let year = move_matches.get_one::<String>("year").unwrap();
+            move_board_item(api, item_id, year).await?;
+        }
+        Some(("duplicate", duplicate_matches)) => {
+            let item_id = duplicate_matches.get_one::<String>("id").unwrap();
+            let date = duplicate_matches.get_one::<String>("date");
+            duplicate_board_item(api, item_id, date.map(String::as_str)).await?;
+        }
+        Some(("delete", delete_matches)) => {
+            let item_ids: Vec<&str> = delete_matches
+                .get_many::<String>("id")
+                .unwrap()
+                .map(|id| id.trim())
+                .filter(|id| !id.is_empty())
+                .collect();
+            delete_items(api, &item_ids, delete_matches.get_flag("force")).await?;
+        }
+        Some(("update", update_matches)) => {
+            let item_id = update_matches.get_one::<String>("id").unwrap();
+            // Checked up front so a bad value doesn't leave the item half updated
+            let column_values = column_sets(update_matches)?;
+            if let Some(name) = update_matches.get_one::<String>("name") {
+                rename_item(api, item_id, name).await?;
+            }
+            if !column_values.is_empty() {
+                set_item_columns(api, item_id, column_values).await?;
+            }
+        }
+        Some(("update-hours", update_matches)) => {
+            let item_id = update_matches.get_one::<String>("id").unwrap();
+            let hours = update_matches.get_one::<String>("hours").unwrap();
+            update_item_hours(api, item_id, hours).await?;
+        }
+        Some(("summary", summary_matches)) => {
+            let year = summary_matches.get_one::<String>("year");
+            let format = Format::from_matches(summary_matches);
+            format.keep_stdout_clean();
+            summarize_by_client(api, year.map(String::as_str), format).await?;
+        }
+        Some(("stats", stats_matches)) => {
+            let format = Format::from_matches(stats_matches);
+            format.keep_stdout_clean();
+            summarize_by_activity(
+                api,
+                stats_matches.get_one::<String>("year").map(String::as_str),
+                stats_matches.get_flag("mine"),
+                format,
+            )
+            .await?;
+        }
+        Some(("export", export_matches)) => {
+            let path = export_matches.get_one::<String>("path").unwrap();
+            if Path::new(path).exists()
+                && !export_matches.get_flag("overwrite")
+                && !prompt::confirm(
+                    &format!("{} already exists, replace it?", path),
+                    "--overwrite",
+                )?
+            {
+                return Err(anyhow!(
+                    "{} already exists, pass --overwrite to replace it",
+                    path
+                ));
+            }
+            let year = export_matches.get_one::<String>("year");
+            export_items(api, path, year.map(String::as_str)).await?;
+        }
+        Some(("recent", recent_matches)) => {
+            let count = *recent_matches.get_one::<u32>("count").unwrap() as usize;
+            show_recent_items(api, count).await?;
+        }
+        Some(("search", search_matches)) => {
+            let filter = |id: &str| search_matches.get_one::<String>(id).map(String::as_str);
+            let filters = SearchFilters {
+                client: filter("client"),
+                wi: filter("wi"),
+                name: filter("name"),
+            };
+            search_items(api, &filters).await?;
+        }
+        Some(("groups", groups_matches)) => {
+            let mut groups = api.get_groups().await?;
+            sort_groups(
+                &mut groups,
+                groups_matches.get_one::<String>("group-order").unwrap(),
+            );
+            if groups_matches.get_flag("json") {
+                println!("{}", serde_json::to_string_pretty(&groups)?);
+            } else {
+                print_groups_table(&mut io::stdout().lock(), &groups)?;
+            }
+        }
+        Some(("activities", _)) => {
+            print_activities(api.config());
+        }
+        Some(("columns", _)) => {
+            list_board_columns(api).await?;
+        }
+        Some(("check", _)) => {
+            check_config(api).await?;
+        }
+        Some(("ping", ping_matches)) => {
+            ping_api(api, *ping_matches.get_one::<u32>("count").unwrap()).await?;
+        }
+        Some(("bulk-add", bulk_matches)) => {
+            let file = bulk_matches.get_one::<String>("file").unwrap();
+            let skip_invalid = bulk_matches.get_flag("skip-invalid");
+            let concurrency = *bulk_matches.get_one::<u32>("concurrency").unwrap() as usize;
+            bulk_add_items(api, file, skip_invalid, concurrency).await?;
+        }
+        _ => {
+            println!("No subcommand provided. Use --help for usage information.");
+        }
+    }
+
+    Ok(())
+}
+
+/// Ask for the API key, or read it from stdin when piped, and store it in the keychain.
+fn login() -> Result<()> {
+    let api_key = prompt::secret("API key")?;
+    if api_key.is_empty() {
+        return Err(CliError::InvalidInput("The API key can't be empty".to_string()).into());
+    }
+    secrets::store_api_key(&api_key)?;
+    output::success("Stored the API key in the keychain");
+    output::info(format!(
+        "Set api_key = \"{}\" in the config, or pass --keyring, to use it",
+        secrets::KEYRING_API_KEY
+    ));
+    Ok(())
+}
+
+fn logout() -> Result<()> {
+    if secrets::delete_api_key()? {
+        output::success("Removed the API key from the keychain");
+    } else {
+        output::info("No API key was stored in the keychain");
+    }
+    Ok(())
+}
+
+/// Write the config template and explain how to fill it in.
+fn init_config(path: Option<PathBuf>, force: bool) -> Result<()> {
+    let path = path
+        .or_else(Config::user_config_path)
+        .ok_or_else(|| anyhow!("No user config directory available, pass a path"))?;
+    Config::write_template(&path, force)?;
+
+    output::success(format!("Wrote a config template to {}", path.display()));
+    println!();
+    println!("Replace the placeholders before using the tool:");
+    println!("  - api_key: Monday.com → Your profile → Admin → API → Generate new API token");
+    println!("  - board_id: the number in the URL of your board");
+    println!("  - user_id: your numeric user ID");
+    println!();
+    println!("Then run `monday-claim check` to verify the settings.");
+    Ok(())
+}
+
+/// Parse an optional `YYYY-MM-DD` query bound such as `--from`.
+fn parse_date_bound(matches: &clap::ArgMatches, name: &str) -> Result<Option<NaiveDate>> {
+    matches
+        .get_one::<String>(name)
+        .map(|value| {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| {
+                CliError::InvalidInput(format!(
+                    "Invalid --{} date: {}, expected format: YYYY-MM-DD",
+                    name, value
+                ))
+                .into()
+            })
+        })
+        .transpose()
+}
+
+/// How a read command prints its results, chosen with `--format`.
+#[derive(Clone, Copy, PartialEq)]
+enum Format {
+    Table,
+    Json,
+    Csv,
+    Markdown,
+}
+
+impl Format {
+    /// The format asked for, counting the older `--json` and `query --csv` flags.
+    fn from_matches(matches: &ArgMatches) -> Self {
+        if matches!(matches.try_get_one::<bool>("json"), Ok(Some(true))) {
+            return Format::Json;
+        }
+        if matches!(matches.try_get_one::<String>("csv"), Ok(Some(_))) {
+            return Format::Csv;
+        }
+        match matches.get_one::<String>("format").map(String::as_str) {
+            Some("json") => Format::Json,
+            Some("csv") => Format::Csv,
+            Some("markdown") => Format::Markdown,
+            _ => Format::Table,
+        }
+    }
+
+    /// Move the status lines to stderr when results in this format go to stdout, so a warning
+    /// can't end up in the middle of JSON, CSV or Markdown meant for another tool.
+    fn keep_stdout_clean(self) {
+        if self != Format::Table {
+            output::status_to_stderr();
+        }
+    }
+}
+
+/// Everything the `query` subcommand can be asked to do with the fetched items.
+struct QueryOptions<'a> {
+    // Page size, and the number of items fetched unless `fetch_all` is set
+    limit: u32,
+    fetch_all: bool,
+    // Costs extra API complexity, so subitems are only fetched when asked for
+    with_subitems: bool,
+    // Resume after this page instead of starting at the first one
+    cursor: Option<&'a str>,
+    show_cursor: bool,
+    include_archived: bool,
+    // Read the items of the last online query from the cache instead of fetching them
+    offline: bool,
+    // Only keep items newer than the marker in the state file, then move it to the newest
+    since_last: bool,
+    reset_marker: bool,
+    // Only keep items assigned to the configured user
+    mine: bool,
+    // Only keep items in the group with this title
+    year: Option<&'a str>,
+    // Inclusive bounds on the date column
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    // One of "date", "hours" or "name"
+    sort: Option<&'a str>,
+    descending: bool,
+    // One of "asc", "desc" or "api", see `sort_groups`
+    group_order: &'a str,
+    // Longest cell in the items table before it's cut off with an ellipsis
+    max_col_width: Option<usize>,
+    // Mark unset columns with NULL_MARKER in the table and Markdown output
+    show_null: bool,
+    // Comma-separated column IDs or [columns] setting names to show instead of every column
+    columns: Option<&'a str>,
+    // Like `columns`, but narrows the column values requested from the API
+    fields: Option<&'a str>,
+    format: Format,
+    // File to write the output to instead of stdout
+    destination: Option<&'a str>,
+    // Add to the end of the CSV file instead of replacing it, optionally skipping known IDs
+    append: bool,
+    dedup: bool,
+}
+
+async fn extract_board_items(api: &MondayClient, options: &QueryOptions<'_>) -> Result<()> {
+    let config = api.config();
+    let limit = options.limit;
+
+    let mut board = if options.offline {
+        load_cached_board(config.board_id)?
+    } else {
+        if let Some(list) = options.fields {
+            // Checked up front, so a typo doesn't cost every page of an --all run first
+            select_columns(config, &api.get_columns().await?, &[], list)?;
+        }
+        let field_ids = options.fields.map(|list| column_list_ids(config, list));
+        let board = fetch_board(
+            api,
+            limit,
+            options.fetch_all,
+            options.with_subitems,
+            options.include_archived,
+            options.cursor,
+            field_ids.as_deref(),
+        )
+        .await?;
+        // Only a whole board is worth showing offline, not a page from the middle of it or
+        // some of its columns
+        if options.cursor.is_none() && options.fields.is_none() {
+            if let Err(e) = cache::store_items(config.board_id, &board) {
+                warn!("Could not cache the board's items: {}", e);
+            }
+        }
+        board
+    };
+    // Taken before filtering, which doesn't change where the next page starts
+    let next_cursor = board.items_page.cursor.clone();
+
+    if options.reset_marker {
+        state::store_query_marker(config.board_id, None)?;
+        info!(
+            "Forgot the --since-last marker of board {}",
+            config.board_id
+        );
+    }
+    // Item IDs only grow, so anything above the highest one listed last time is new
+    let marker = if options.since_last {
+        state::query_marker(config.board_id)
+    } else {
+        None
+    };
+    let newest = board
+        .items_page
+        .items
+        .iter()
+        .filter_map(|item| item.id.parse::<u64>().ok())
+        .max();
+    if let Some(marker) = marker {
+        board
+            .items_page
+            .items
+            .retain(|item| item.id.parse::<u64>().is_ok_and(|id| id > marker));
+    }
+
+    if options.mine {
+        board
+            .items_page
+            .items
+            .retain(|item| is_assigned_to_user(config, item));
+    }
+
+    if let Some(year) = options.year {
+        let group_id = group_by_title(&board.groups, year)?.id.clone();
+        board
+            .items_page
+            .items
+            .retain(|item| item.group.id == group_id);
+    }
+
+    if options.from.is_some() || options.to.is_some() {
+        // Items without a date can't be placed in the range, so they're dropped
+        board.items_page.items.retain(|item| {
+            NaiveDate::parse_from_str(&decoded_column(item, &config.columns.date), "%Y-%m-%d")
+                .is_ok_and(|date| {
+                    options.from.is_none_or(|from| date >= from)
+                        && options.to.is_none_or(|to| date <= to)
+                })
+        });
+    }
+
+    if let Some(key) = options.sort {
+        sort_items(config, &mut board.items_page.items, key, options.descending);
+    }
+    if options.group_order != "api" {
+        sort_groups(&mut board.groups, options.group_order);
+        // Stable, so --sort still orders the items within each group
+        let groups = &board.groups;
+        board.items_page.items.sort_by_key(|item| {
+            groups
+                .iter()
+                .position(|group| group.id == item.group.id)
+                .unwrap_or(groups.len())
+        });
+    }
+
+    let only_columns = options
+        .columns
+        .map(|list| select_columns(config, &board.columns, &board.items_page.items, list))
+        .transpose()?;
+
+    let lookups = if options.offline {
+        // Person names would have to be fetched, so their IDs are shown instead
+        Lookups {
+            status_labels: status_labels(&board.columns),
+            transforms: config.transforms.clone(),
+            ..Default::default()
+        }
+    } else {
+        resolve_lookups(api, &board.items_page.items, &board.columns).await
+    };
+
+    let items = &board.items_page.items;
+    match (options.format, options.destination) {
+        // Written by path, so the rows can be added to an existing file
+        (Format::Csv, Some(path)) => {
+            let written = write_items_csv(
+                path,
+                &board.items_page.items,
+                &board.groups,
+                &lookups,
+                options.append,
+                options.dedup,
+            )?;
+            if options.append {
+                output::success(format!("Appended {} items to {}", written, path));
+                let skipped = board.items_page.items.len() - written;
+                if skipped > 0 {
+                    output::info(format!("Left out {} items already in the file", skipped));
+                }
+            } else {
+                output::success(format!("Wrote {} items to {}", written, path));
+            }
+        }
+        (format, destination) => {
+            let mut out: Box<dyn Write> = match destination {
+                Some(path) => Box::new(BufWriter::new(
+                    File::create(path).map_err(|e| anyhow!("Failed to create {}: {}", path, e))?,
+                )),
+                None => Box::new(io::stdout().lock()),
+            };
+            match format {
+                Format::Table => {
+                    // Print groups information
+                    print_groups_table(&mut out, &board.groups)?;
+
+                    // Print items information
+                    print_items_table(
+                        &mut out,
+                        items,
+                        &board.groups,
+                        &lookups,
+                        options.max_col_width,
+                        only_columns.as_deref(),
+                        options.show_null,
+                    )?;
+                    print_hours_summary(&mut out, config, items)?;
+                }
+                Format::Markdown => print_items_markdown(
+                    &mut out,
+                    items,
+                    &board.groups,
+                    &lookups,
+                    only_columns.as_deref(),
+                    options.show_null,
+                )?,
+                Format::Json => print_items_json(&mut out, items, &board.groups, &lookups)?,
+                Format::Csv => {
+                    write_csv_rows(
+                        csv::Writer::from_writer(&mut out),
+                        items,
+                        &board.groups,
+                        &lookups,
+                        None,
+                        &BTreeSet::new(),
+                    )?;
+                }
+            }
+            out.flush()?;
+
+            if let Some(path) = destination {
+                output::success(format!("Wrote {} items to {}", items.len(), path));
+            }
+        }
+    }
+
+    // The marker covers every item, so a run that left some out doesn't move it
+    let filtered =
+        options.mine || options.year.is_some() || options.from.is_some() || options.to.is_some();
+    if options.since_last && !filtered {
+        if let Some(newest) = newest.max(marker) {
+            state::store_query_marker(config.board_id, Some(newest))?;
+        }
+    }
+
+    if options.show_cursor {
+        // On stderr so it can be read alongside JSON or CSV output without corrupting it
+        match next_cursor {
+            Some(cursor) => eprintln!("Next cursor: {}", cursor),
+            None => eprintln!("Next cursor: none, this was the last page"),
+        }
+    }
+
+    Ok(())
+}
+
+/// The board and items cached by the last online `query`, for `query --offline`.
+fn load_cached_board(board_id: u64) -> Result<Board> {
+    let cached = cache::load_items(board_id).ok_or_else(|| {
+        anyhow!(
+            "No items of board {} are cached yet, run query once without --offline",
+            board_id
+        )
+    })?;
+    warn!(
+        "Offline: showing the items fetched {} ago, they may be out of date",
+        cache::describe_age(cached.age())
+    );
+    Ok(cached.board)
+}
+
+/// Fetch the board with its first page of items, or every item when `fetch_all` is set.
+///
+/// With a `start_cursor` the items start at the page after it instead of the first page.
+async fn fetch_board(
+    api: &MondayClient,
+    limit: u32,
+    fetch_all: bool,
+    with_subitems: bool,
+    include_archived: bool,
+    start_cursor: Option<&str>,
+    column_ids: Option<&[String]>,
+) -> Result<Board> {
+    let mut limit = limit;
+    let mut retries = api.config().complexity_retries;
+    let mut board = with_complexity_retry(&mut limit, &mut retries, |limit| {
+        api.get_board_structure(
+            limit,
+            start_cursor.is_none(),
+            with_subitems,
+            include_archived,
+            column_ids,
+        )
+    })
+    .await?;
+    if let Some(cursor) = start_cursor {
+        info!("Fetching the page after cursor {}", cursor);
+        board.items_page = with_complexity_retry(&mut limit, &mut retries, |limit| {
+            api.next_items_page(limit, cursor, with_subitems, column_ids)
+        })
+        .await?;
+    }
+
+    if fetch_all {
+        // Keep requesting pages until the API stops handing out cursors
+        let mut cursor = board.items_page.cursor.take();
+        let progress = Progress::spinner("Fetching pages of items");
+        while let Some(current) = cursor {
+            info!("Fetching next page of items...");
+            let page = with_complexity_retry(&mut limit, &mut retries, |limit| {
+                api.next_items_page(limit, &current, with_subitems, column_ids)
+            })
+            .await?;
+            board.items_page.items.extend(page.items);
+            cursor = page.cursor;
+            progress.inc(1);
+        }
+    }
+
+    Ok(board)
+}
+
+/// Run `fetch` with `limit` items per page, halving the limit and trying again while Monday
+/// rejects the query as too complex and `retries` are left. The lowered limit is kept for the
+/// pages still to come.
+async fn with_complexity_retry<T, F, Fut>(
+    limit: &mut u32,
+    retries: &mut u32,
+    mut fetch: F,
+) -> Result<T>
+where
+    F: FnMut(u32) -> Fut,
+    Fut: std::future::Future<Output = Result<T>>,
+{
+    loop {
+        match fetch(*limit).await {
+            Err(e) if *retries > 0 && *limit > 1 && is_complexity_error(&e) => {
+                *retries -= 1;
+                *limit /= 2;
+                output::warning(format!(
+                    "The query was too complex for Monday.com, retrying with {} items per page",
+                    limit
+                ));
+            }
+            result => return result,
+        }
+    }
+}
+
+fn is_complexity_error(error: &anyhow::Error) -> bool {
+    error
+        .downcast_ref::<CliError>()
+        .and_then(CliError::graphql_kind)
+        == Some(GraphQLErrorKind::ComplexityBudget)
+}
+
+/// Write every item, or those of one year group, to an XLSX workbook.
+async fn export_items(api: &MondayClient, path: &str, year: Option<&str>) -> Result<()> {
+    let mut board = fetch_board(api, MAX_PAGE_LIMIT, true, false, false, None, None).await?;
+    if let Some(year) = year {
+        let group_id = group_by_title(&board.groups, year)?.id.clone();
+        board
+            .items_page
+            .items
+            .retain(|item| item.group.id == group_id);
+    }
+
+    let lookups = resolve_lookups(api, &board.items_page.items, &board.columns).await;
+    let sheets = xlsx::write_items(path, api.config(), &board, &lookups)?;
+    output::success(format!(
+        "Wrote {} items in {} sheet(s) to {}",
+        board.items_page.items.len(),
+        sheets,
+        path
+    ));
+    Ok(())
+}
+
+/// Print the `count` items assigned to the configured user with the latest dates.
+async fn show_recent_items(api: &MondayClient, count: usize) -> Result<()> {
+    let config = api.config();
+    let mut board = fetch_board(api, MAX_PAGE_LIMIT, true, false, false, None, None).await?;
+    let items = &mut board.items_page.items;
+    items.retain(|item| is_assigned_to_user(config, item));
+    sort_items(config, items, "date", true);
+    items.truncate(count);
+    if items.is_empty() {
+        println!("No items found for user {}.", config.user_id);
+        return Ok(());
+    }
+
+    let lookups = resolve_lookups(api, items, &board.columns).await;
+    print_items_table(
+        &mut io::stdout().lock(),
+        items,
+        &board.groups,
+        &lookups,
+        None,
+        None,
+        false,
+    )?;
+    Ok(())
+}
+
+/// Substrings an item must contain to be listed by `search`, `None` where any value matches.
+struct SearchFilters<'a> {
+    client: Option<&'a str>,
+    wi: Option<&'a str>,
+    name: Option<&'a str>,
+}
+
+impl SearchFilters<'_> {
+    /// Whether the item contains every given filter, ignoring case.
+    fn matches(&self, config: &Config, item: &models::Item) -> bool {
+        let contains = |value: &str, filter: Option<&str>| {
+            filter.is_none_or(|filter| value.to_lowercase().contains(&filter.to_lowercase()))
+        };
+        contains(&decoded_column(item, &config.columns.client), self.client)
+            && contains(&decoded_column(item, &config.columns.work_item), self.wi)
+            && contains(&item.name, self.name)
+    }
+}
+
+/// Print the items anywhere on the board that match all the filters.
+async fn search_items(api: &MondayClient, filters: &SearchFilters<'_>) -> Result<()> {
+    let config = api.config();
+    let mut board = fetch_board(api, MAX_PAGE_LIMIT, true, false, false, None, None).await?;
+    board
+        .items_page
+        .items
+        .retain(|item| filters.matches(config, item));
+
+    let lookups = resolve_lookups(api, &board.items_page.items, &board.columns).await;
+    print_items_table(
+        &mut io::stdout().lock(),
+        &board.items_page.items,
+        &board.groups,
+        &lookups,
+        None,
+        None,
+        false,
+    )?;
+    Ok(())
+}
+
+/// Print the hours and number of items per client across the whole board, or one year group.
+async fn summarize_by_client(api: &MondayClient, year: Option<&str>, format: Format) -> Result<()> {
+    let config = api.config();
+    // Largest pages the API allows, to keep the number of round trips down
+    let mut board = fetch_board(api, MAX_PAGE_LIMIT, true, false, false, None, None).await?;
+
+    if let Some(year) = year {
+        let group_id = group_by_title(&board.groups, year)?.id.clone();
+        board
+            .items_page
+            .items
+            .retain(|item| item.group.id == group_id);
+    }
+
+    let mut skipped = 0;
+    let mut by_client: BTreeMap<String, (f64, usize)> = BTreeMap::new();
+    for item in &board.items_page.items {
+        let client = decoded_column(item, &config.columns.client);
+        let client = match client.trim() {
+            "" => "(none)".to_string(),
+            client => client.to_string(),
+        };
+        let entry = by_client.entry(client).or_default();
+        entry.1 += 1;
+        match decoded_column(item, &config.columns.hours)
+            .trim()
+            .parse::<f64>()
+        {
+            Ok(hours) => entry.0 += hours,
+            Err(_) => skipped += 1,
+        }
+    }
+
+    let summaries: Vec<ClientSummary> = by_client
+        .into_iter()
+        .map(|(client, (hours, items))| ClientSummary {
+            client,
+            hours,
+            items,
+        })
+        .collect();
+
+    if format == Format::Json {
+        return print_json(&summaries);
+    }
+
+    let header = ["Client", "Hours", "Items"].map(String::from);
+    let mut rows: Vec<Vec<String>> = summaries
+        .iter()
+        .map(|summary| {
+            vec![
+                summary.client.clone(),
+                summary.hours.to_string(),
+                summary.items.to_string(),
+            ]
+        })
+        .collect();
+    if format == Format::Csv {
+        return print_csv_rows(&header, &rows);
+    }
+
+    if summaries.is_empty() {
+        println!("No items found in the board.");
+        return Ok(());
+    }
+
+    let total_hours: f64 = summaries.iter().map(|summary| summary.hours).sum();
+    let total_items: usize = summaries.iter().map(|summary| summary.items).sum();
+    rows.push(vec![
+        "Total".to_string(),
+        total_hours.to_string(),
+        total_items.to_string(),
+    ]);
+
+    if format == Format::Table {
+        println!("Hours by client:");
+    }
+    print_rows(&mut io::stdout().lock(), format, &header, &rows)?;
+    if skipped > 0 {
+        output::warning(format!(
+            "{} items had no parseable hours and were not counted, the totals are incomplete",
+            skipped
+        ));
+    }
+    Ok(())
+}
+
+/// Print the hours per activity and their share of the total, most hours first.
+///
+/// Activities are named by the board's status labels, falling back to the activity keywords.
+async fn summarize_by_activity(
+    api: &MondayClient,
+    year: Option<&str>,
+    mine: bool,
+    format: Format,
+) -> Result<()> {
+    let config = api.config();
+    let mut board = fetch_board(api, MAX_PAGE_LIMIT, true, false, false, None, None).await?;
+
+    if let Some(year) = year {
+        let group_id = group_by_title(&board.groups, year)?.id.clone();
+        board
+            .items_page
+            .items
+            .retain(|item| item.group.id == group_id);
+    }
+    if mine {
+        board
+            .items_page
+            .items
+            .retain(|item| is_assigned_to_user(config, item));
+    }
+
+    let labels = status_labels(&board.columns)
+        .remove(&config.columns.status)
+        .unwrap_or_default();
+    let mut skipped = 0;
+    let mut by_activity: HashMap<String, f64> = HashMap::new();
+    for item in &board.items_page.items {
+        let Ok(hours) = decoded_column(item, &config.columns.hours)
+            .trim()
+            .parse::<f64>()
+        else {
+            skipped += 1;
+            continue;
+        };
+        let status = decoded_column(item, &config.columns.status);
+        let activity = match status.trim().parse::<i64>() {
+            Ok(index) => labels
+                .get(&index)
+                .cloned()
+                .or_else(|| config.activity_name(index).filter(|name| !name.is_empty()))
+                .unwrap_or_else(|| format!("status {}", index)),
+            Err(_) => "(no status)".to_string(),
+        };
+        *by_activity.entry(activity).or_default() += hours;
+    }
+
+    let total: f64 = by_activity.values().sum();
+    let mut summaries: Vec<ActivitySummary> = by_activity
+        .into_iter()
+        .map(|(activity, hours)| ActivitySummary {
+            activity,
+            hours,
+            percent: if total > 0.0 {
+                (hours / total * 1000.0).round() / 10.0
+            } else {
+                0.0
+            },
+        })
+        .collect();
+    summaries.sort_by(|a, b| {
+        b.hours
+            .total_cmp(&a.hours)
+            .then_with(|| a.activity.cmp(&b.activity))
+    });
+
+    if format == Format::Json {
+        return print_json(&summaries);
+    }
+
+    let header = ["Activity", "Hours", "Share"].map(String::from);
+    let mut rows: Vec<Vec<String>> = summaries
+        .iter()
+        .map(|summary| {
+            vec![
+                summary.activity.clone(),
+                summary.hours.to_string(),
+                format!("{:.1}%", summary.percent),
+            ]
+        })
+        .collect();
+    if format == Format::Csv {
+        return print_csv_rows(&header, &rows);
+    }
+
+    if summaries.is_empty() {
+        println!("No items found in the board.");
+        return Ok(());
+    }
+
+    rows.push(vec![
+        "Total".to_string(),
+        total.to_string(),
+        "100.0%".to_string(),
+    ]);
+
+    if format == Format::Table {
+        println!("Hours by activity:");
+    }
+    print_rows(&mut io::stdout().lock(), format, &header, &rows)?;
+    if skipped > 0 {
+        output::warning(format!(
+            "{} items had no parseable hours and were not counted, the totals are incomplete",
+            skipped
+        ));
+    }
+    Ok(())
+}
+
+/// Order items by date, hours or name; items without a usable value go last either way.
+fn sort_items(config: &Config, items: &mut [models::Item], key: &str, descending: bool) {
+    match key {
+        "date" => sort_missing_last(items, descending, |item| {
+            NaiveDate::parse_from_str(&decoded_column(item, &config.columns.date), "%Y-%m-%d").ok()
+        }),
+        "hours" => sort_missing_last(items, descending, |item| {
+            decoded_column(item, &config.columns.hours)
+                .trim()
+                .parse::<f64>()
+                .ok()
+                .filter(|hours| hours.is_finite())
+        }),
+        _ => sort_missing_last(items, descending, |item| {
+            Some(item.name.to_lowercase()).filter(|name| !name.is_empty())
+        }),
+    }
+}
+
+/// Order groups by title for `--group-order asc` or `desc`, numeric titles such as years by
+/// their value and all others after them; "api" keeps the order the API returned.
+fn sort_groups(groups: &mut [models::Group], order: &str) {
+    if order == "api" {
+        return;
+    }
+    let descending = order == "desc";
+    groups.sort_by(|a, b| {
+        let ordering = match (a.title.trim().parse::<i64>(), b.title.trim().parse::<i64>()) {
+            (Ok(a), Ok(b)) => a.cmp(&b),
+            (Ok(_), Err(_)) => return Ordering::Less,
+            (Err(_), Ok(_)) => return Ordering::Greater,
+            (Err(_), Err(_)) => a.title.to_lowercase().cmp(&b.title.to_lowercase()),
+        };
+        if descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    });
+}
+
+fn sort_missing_last<T, F>(items: &mut [models::Item], descending: bool, key: F)
+where
+    T: PartialOrd,
+    F: Fn(&models::Item) -> Option<T>,
+{
+    items.sort_by(|a, b| match (key(a), key(b)) {
+        (Some(a), Some(b)) => {
+            let ordering = a.partial_cmp(&b).unwrap_or(Ordering::Equal);
+            if descending {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        }
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    });
+}
+
+async fn show_board_item(
+    api: &MondayClient,
+    item_id: &str,
+    fields: Option<&str>,
+    format: Format,
+) -> Result<()> {
+    let config = api.config();
+    let field_ids = fields.map(|list| column_list_ids(config, list));
+    let details = api.get_item(item_id, field_ids.as_deref()).await?;
+    if let Some(list) = fields {
+        select_columns(config, &details.board.columns, &[], list)?;
+    }
+    let item = &details.item;
+    let groups = &details.board.groups;
+
+    let lookups = resolve_lookups(api, std::slice::from_ref(item), &details.board.columns).await;
+    let mut out = io::stdout().lock();
+    print_item_details(&mut out, format, item, groups, &lookups)?;
+    out.flush()?;
+    Ok(())
+}
+
+/// Fail if any `[columns]` setting points at a column the board doesn't have, for `--strict`.
+async fn verify_column_ids(api: &MondayClient) -> Result<()> {
+    let config = api.config();
+    let columns = api.get_columns().await?;
+    let missing: Vec<String> = config
+        .columns
+        .settings()
+        .iter()
+        .filter(|(_, id)| !columns.iter().any(|column| column.id == *id))
+        .map(|(setting, id)| format!("  - {} = \"{}\"", setting, id))
+        .collect();
+    if !missing.is_empty() {
+        return Err(anyhow!(
+            "Columns configured in [columns] don't exist on board {}:\n{}\nRun `monday-claim columns` to list the board's column IDs",
+            config.board_id,
+            missing.join("\n")
+        ));
+    }
+    info!("All configured column IDs exist on the board");
+    Ok(())
+}
+
+async fn list_board_columns(api: &MondayClient) -> Result<()> {
+    let columns = api.get_columns().await?;
+    if columns.is_empty() {
+        println!("No columns found in the board.");
+        return Ok(());
+    }
+
+    // Show which [columns] setting currently points at each column
+    let settings = api.config().columns.settings();
+
+    let mut table = Table::new();
+    table.add_row(row!["Column ID", "Title", "Type", "Used as"]);
+    for column in &columns {
+        let used_as: Vec<&str> = settings
+            .iter()
+            .filter(|(_, id)| *id == column.id)
+            .map(|(setting, _)| *setting)
+            .collect();
+        table.add_row(row![
+            column.id,
+            column.title,
+            column.column_type,
+            used_as.join(", ")
+        ]);
+    }
+
+    println!("Found {} columns:", columns.len());
+    table.printstd();
+    Ok(())
+}
+
+/// Verify the config, API key, board and user ID, printing one line per check.
+async fn check_config(api: &MondayClient) -> Result<()> {
+    let config = api.config();
+    output::success("Config parsed");
+
+    let mut failures = 0;
+
+    let me = match api.me().await {
+        Ok(me) => {
+            output::success(format!("API key valid (authenticated as {})", me.name));
+            Some(me)
+        }
+        Err(e) => {
+            output::failure(format!("API key valid: {}", e));
+            if let Some(hint) = e.downcast_ref::<CliError>().and_then(CliError::hint) {
+                println!("   💡 {}", hint);
+            }
+            failures += 1;
+            None
+        }
+    };
+
+    match api.get_board_summary().await {
+        Ok(board) => match board {
+            Some(board) => {
+                output::success(format!("Board reachable ({}: {})", board.id, board.name))
+            }
+            None => {
+                output::failure(format!(
+                    "Board reachable: no board found with ID {}",
+                    config.board_id
+                ));
+                failures += 1;
+            }
+        },
+        Err(e) => {
+            output::failure(format!("Board reachable: {}", e));
+            failures += 1;
+        }
+    }
+
+    match me {
+        Some(me) if me.id == config.user_id.to_string() => {
+            output::success("user_id matches the authenticated user")
+        }
+        Some(me) => {
+            output::failure(format!(
+                "user_id matches the authenticated user: config has {}, API key belongs to {}",
+                config.user_id, me.id
+            ));
+            failures += 1;
+        }
+        None => {
+            output::failure(
+                "user_id matches the authenticated user: could not check without a valid API key",
+            );
+            failures += 1;
+        }
+    }
+
+    if failures > 0 {
+        return Err(anyhow!("{} check(s) failed", failures));
+    }
+    Ok(())
+}
+
+/// Send `count` trivial queries one after the other and print the fastest, average and slowest
+/// round trip, to tell a slow network or API apart from a slow command.
+async fn ping_api(api: &MondayClient, count: u32) -> Result<()> {
+    let mut timings = Vec::new();
+    let mut user = None;
+    for attempt in 1..=count {
+        let started = Instant::now();
+        match api.me().await {
+            Ok(me) => user = Some(me),
+            Err(e) => {
+                output::failure(format!(
+                    "Request {} of {} failed after {} ms",
+                    attempt,
+                    count,
+                    started.elapsed().as_millis()
+                ));
+                return Err(e);
+            }
+        }
+        timings.push(started.elapsed());
+    }
+
+    if let Some(me) = user {
+        output::success(format!("API key valid (authenticated as {})", me.name));
+    }
+    let min = timings.iter().min().copied().unwrap_or_default();
+    let max = timings.iter().max().copied().unwrap_or_default();
+    let average = timings.iter().sum::<Duration>() / count;
+    println!(
+        "{} request(s) to {}: min {} ms, avg {} ms, max {} ms",
+        count,
+        api.config().api_url,
+        min.as_millis(),
+        average.as_millis(),
+        max.as_millis()
+    );
+    Ok(())
+}
+
+fn print_groups_table(out: &mut dyn Write, groups: &[models::Group]) -> io::Result<()> {
+    if groups.is_empty() {
+        writeln!(out, "No groups found in the board.")?;
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    table.add_row(row!["Group ID", "Group Title"]);
+
+    for group in groups {
+        table.add_row(row![group.id, group.title]);
+    }
+
+    writeln!(out, "Found {} groups:", groups.len())?;
+    table.print(out)?;
+    writeln!(out) // Add empty line for separation
+}
+
+/// The header and the displayed cells of every item, subitems indented under their parent.
+///
+/// `only_columns` limits the columns shown after the ID, name and group, in its order.
+fn item_rows(
+    items: &[models::Item],
+    groups: &[models::Group],
+    lookups: &Lookups,
+    only_columns: Option<&[String]>,
+    show_null: bool,
+) -> (Vec<String>, Vec<Vec<String>>) {
+    let group_mapping = group_title_map(groups);
+    let cell = |item: &models::Item, column_id: &str| {
+        let unset = !item
+            .column_values
+            .iter()
+            .any(|column| column.id == column_id && column.value.is_some());
+        if show_null && unset {
+            NULL_MARKER.to_string()
+        } else {
+            display_column(item, column_id, lookups)
+        }
+    };
+
+    // Subitems live on their own board with columns of their own, show those too
+    let column_ids = match only_columns {
+        Some(columns) => columns.to_vec(),
+        None => {
+            let mut column_ids = collect_column_ids(items);
+            for subitem in items.iter().flat_map(|item| &item.subitems) {
+                for column in &subitem.column_values {
+                    if !column_ids.contains(&column.id) {
+                        column_ids.push(column.id.clone());
+                    }
+                }
+            }
+            column_ids
+        }
+    };
+
+    let mut header = vec!["ID".to_string(), "Name".to_string(), "Group".to_string()];
+    for column_id in &column_ids {
+        header.push(format!("Column {}", column_id));
+    }
+
+    let mut rows = Vec::new();
+    for item in items {
+        let group_name = group_mapping
+            .get(item.group.id.as_str())
+            .unwrap_or(&"Unknown");
+
+        let name = if item.is_archived() {
+            format!("{} (archived)", item.name)
+        } else {
+            item.name.clone()
+        };
+        let mut row = vec![item.id.clone(), name, group_name.to_string()];
+        for column_id in &column_ids {
+            row.push(cell(item, column_id));
+        }
+        rows.push(row);
+
+        // Indented under their parent, which they share the group with
+        for subitem in &item.subitems {
+            let mut row = vec![
+                format!("  ↳ {}", subitem.id),
+                format!("  {}", subitem.name),
+                group_name.to_string(),
+            ];
+            for column_id in &column_ids {
+                row.push(cell(subitem, column_id));
+            }
+            rows.push(row);
+        }
+    }
+    (header, rows)
+}
+
+fn print_items_table(
+    out: &mut dyn Write,
+    items: &[models::Item],
+    groups: &[models::Group],
+    lookups: &Lookups,
+    max_col_width: Option<usize>,
+    only_columns: Option<&[String]>,
+    show_null: bool,
+) -> io::Result<()> {
+    if items.is_empty() {
+        writeln!(out, "No items found in the board.")?;
+        return Ok(());
+    }
+
+    let (header, rows) = item_rows(items, groups, lookups, only_columns, show_null);
+    let to_row = |cells: &[String]| {
+        prettytable::Row::new(
+            cells
+                .iter()
+                .map(|value| prettytable::Cell::new(&truncate(value, max_col_width)))
+                .collect(),
+        )
+    };
+    let mut table = Table::new();
+    table.add_row(to_row(&header));
+    for row in &rows {
+        table.add_row(to_row(row));
+    }
+
+    writeln!(out, "Found {} items:", items.len())?;
+    table.print(out)?;
+    Ok(())
+}
+
+/// The column IDs of a comma-separated list of IDs and `[columns]` setting names, unchecked.
+fn column_list_ids(config: &Config, list: &str) -> Vec<String> {
+    let settings = config.columns.settings();
+    list.split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(|name| {
+            settings
+                .iter()
+                .find(|(setting, _)| *setting == name)
+                .map_or(name, |(_, id)| *id)
+                .to_string()
+        })
+        .collect()
+}
+
+/// Turn a `--columns` list into board column IDs, accepting a `[columns]` setting name such as
+/// `hours` in place of the ID it points at.
+fn select_columns(
+    config: &Config,
+    board_columns: &[models::BoardColumn],
+    items: &[models::Item],
+    list: &str,
+) -> Result<Vec<String>, CliError> {
+    let settings = config.columns.settings();
+    // Subitem columns aren't among the board's, so the values fetched count too
+    let mut known: Vec<&str> = board_columns
+        .iter()
+        .map(|column| column.id.as_str())
+        .collect();
+    for column in items
+        .iter()
+        .flat_map(|item| std::iter::once(item).chain(&item.subitems))
+        .flat_map(|item| &item.column_values)
+    {
+        if !known.contains(&column.id.as_str()) {
+            known.push(&column.id);
+        }
+    }
+    list.split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .zip(column_list_ids(config, list))
+        .map(|(name, id)| {
+            if known.contains(&id.as_str()) {
+                Ok(id)
+            } else {
+                let names: Vec<&str> = settings.iter().map(|(setting, _)| *setting).collect();
+                Err(CliError::InvalidInput(format!(
+                    "Unknown column: {}, valid columns are the [columns] names {} or the column IDs {}",
+                    name,
+                    names.join(", "),
+                    known.join(", ")
+                )))
+            }
+        })
+        .collect()
+}
+
+/// Print the items as a GitHub-flavored Markdown table, e.g. for pasting into a wiki.
+fn print_items_markdown(
+    out: &mut dyn Write,
+    items: &[models::Item],
+    groups: &[models::Group],
+    lookups: &Lookups,
+    only_columns: Option<&[String]>,
+    show_null: bool,
+) -> io::Result<()> {
+    if items.is_empty() {
+        writeln!(out, "No items found in the board.")?;
+        return Ok(());
+    }
+
+    let (header, rows) = item_rows(items, groups, lookups, only_columns, show_null);
+    print_markdown_table(out, &header, &rows)
+}
+
+fn print_markdown_table(
+    out: &mut dyn Write,
+    header: &[String],
+    rows: &[Vec<String>],
+) -> io::Result<()> {
+    let line = |cells: &[String]| {
+        let cells: Vec<String> = cells.iter().map(|cell| markdown_cell(cell)).collect();
+        format!("| {} |", cells.join(" | "))
+    };
+    writeln!(out, "{}", line(header))?;
+    writeln!(out, "|{}", " --- |".repeat(header.len()))?;
+    for row in rows {
+        writeln!(out, "{}", line(row))?;
+    }
+    Ok(())
+}
+
+/// Escape what would end a Markdown table cell early. Leading spaces are kept as non-breaking
+/// ones so subitems stay indented.
+fn markdown_cell(value: &str) -> String {
+    let trimmed = value.trim_start_matches(' ');
+    let indent = "&nbsp;".repeat(value.len() - trimmed.len());
+    let escaped = trimmed
+        .replace('\\', "\\\\")
+        .replace('|', "\\|")
+        .replace("\r\n", "<br>")
+        .replace('\n', "<br>");
+    format!("{}{}", indent, escaped)
+}
+
+/// Cut `value` down to `max_width` characters, ending in an ellipsis when anything was removed.
+fn truncate(value: &str, max_width: Option<usize>) -> String {
+    match max_width {
+        Some(max_width) if value.chars().count() > max_width => {
+            let kept: String = value.chars().take(max_width.saturating_sub(1)).collect();
+            format!("{}…", kept)
+        }
+        _ => value.to_string(),
+    }
+}
+
+/// Print a single item as a vertical field/value table, or as `query` prints an item in the
+/// JSON and CSV formats.
+fn print_item_details(
+    out: &mut dyn Write,
+    format: Format,
+    item: &models::Item,
+    groups: &[models::Group],
+    lookups: &Lookups,
+) -> Result<()> {
+    let group_mapping = group_title_map(groups);
+    match format {
+        Format::Json => {
+            let decoded = decoded_item(item, &group_mapping, lookups);
+            writeln!(out, "{}", serde_json::to_string_pretty(&decoded)?)?;
+            return Ok(());
+        }
+        Format::Csv => {
+            let items = std::slice::from_ref(item);
+            write_csv_rows(
+                csv::Writer::from_writer(out),
+                items,
+                groups,
+                lookups,
+                None,
+                &BTreeSet::new(),
+            )?;
+            return Ok(());
+        }
+        Format::Table | Format::Markdown => {}
+    }
+
+    let group_name = group_mapping
+        .get(item.group.id.as_str())
+        .unwrap_or(&"Unknown");
+    let header = vec!["Field".to_string(), "Value".to_string()];
+    let mut rows = vec![
+        vec!["ID".to_string(), item.id.clone()],
+        vec!["Name".to_string(), item.name.clone()],
+        vec!["Group".to_string(), group_name.to_string()],
+    ];
+    for column in &item.column_values {
+        rows.push(vec![
+            format!("Column {}", column.id),
+            display_column_value(column, lookups),
+        ]);
+    }
+    print_rows(out, format, &header, &rows)?;
+    Ok(())
+}
+
+/// Print a header and rows as a table, or as a Markdown table for `Format::Markdown`.
+fn print_rows(
+    out: &mut dyn Write,
+    format: Format,
+    header: &[String],
+    rows: &[Vec<String>],
+) -> io::Result<()> {
+    if format == Format::Markdown {
+        return print_markdown_table(out, header, rows);
+    }
+    let to_row = |cells: &[String]| {
+        prettytable::Row::new(
+            cells
+                .iter()
+                .map(|cell| prettytable::Cell::new(cell))
+                .collect(),
+        )
+    };
+    let mut table = Table::new();
+    table.add_row(to_row(header));
+    for row in rows {
+        table.add_row(to_row(row));
+    }
+    table.print(out)?;
+    Ok(())
+}
+
+/// Print records as they are for the JSON format.
+fn print_json<T: serde::Serialize>(records: &[T]) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(records)?);
+    Ok(())
+}
+
+/// Print the header and rows of a table as CSV, so the numbers read the same as in the table.
+fn print_csv_rows(header: &[String], rows: &[Vec<String>]) -> Result<()> {
+    let mut writer = csv::Writer::from_writer(io::stdout().lock());
+    writer.write_record(header)?;
+    for row in rows {
+        writer.write_record(row)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+fn print_hours_summary(
+    out: &mut dyn Write,
+    config: &Config,
+    items: &[models::Item],
+) -> io::Result<()> {
+    if items.is_empty() {
+        return Ok(());
+    }
+
+    let mut total = 0.0;
+    let mut skipped = 0;
+    let mut by_activity: BTreeMap<String, f64> = BTreeMap::new();
+
+    for item in items {
+        let Ok(hours) = decoded_column(item, &config.columns.hours)
+            .trim()
+            .parse::<f64>()
+        else {
+            skipped += 1;
+            continue;
+        };
+        total += hours;
+
+        let status = decoded_column(item, &config.columns.status);
+        let activity = match status.parse::<i64>() {
+            Ok(index) => match config.activity_name(index) {
+                Some(name) if !name.is_empty() => name,
+                _ => format!("status {}", index),
+            },
+            Err(_) => "(no status)".to_string(),
+        };
+        *by_activity.entry(activity).or_default() += hours;
+    }
+
+    writeln!(out)?;
+    writeln!(out, "Total hours: {}", total)?;
+    for (activity, hours) in &by_activity {
+        writeln!(out, "  {}: {}", activity, hours)?;
+    }
+    if skipped > 0 {
+        writeln!(
+            out,
+            "⚠️  {} items had no parseable hours and were not counted, the total is incomplete",
+            skipped
+        )?;
+    }
+    Ok(())
+}
+
+/// Write the items to a CSV file, returning how many rows were written.
+///
+/// With `append` the rows go after those already in the file, in the order of its header, and
+/// the header is only written to a new or empty file. `dedup` then skips the items whose ID
+/// the file already has.
+fn write_items_csv(
+    path: &str,
+    items: &[models::Item],
+    groups: &[models::Group],
+    lookups: &Lookups,
+    append: bool,
+    dedup: bool,
+) -> Result<usize> {
+    let (existing_header, known_ids) = if append {
+        read_csv_header_and_ids(path)?
+    } else {
+        (None, BTreeSet::new())
+    };
+    if let Some(header) = &existing_header {
+        let left_out: Vec<String> = collect_column_ids(items)
+            .into_iter()
+            .filter(|column_id| !header[CSV_FIXED_HEADER.len()..].contains(column_id))
+            .collect();
+        if !left_out.is_empty() {
+            warn!(
+                "Leaving out columns {}, the header of {} doesn't have them",
+                left_out.join(", "),
+                path
+            );
+        }
+    }
+
+    let file = if append {
+        fs::OpenOptions::new()
+            .append(true)
+            .create(true)
+            .open(path)
+            .map_err(|e| anyhow!("Failed to open {}: {}", path, e))?
+    } else {
+        File::create(path).map_err(|e| anyhow!("Failed to create {}: {}", path, e))?
+    };
+    let skip_ids = if dedup { known_ids } else { BTreeSet::new() };
+    write_csv_rows(
+        csv::Writer::from_writer(file),
+        items,
+        groups,
+        lookups,
+        existing_header,
+        &skip_ids,
+    )
+}
+
+/// Write a row per item in the columns of `header`, or in the items' own columns after a header
+/// row when there's none yet, leaving out the items in `skip_ids`. Returns the rows written.
+fn write_csv_rows<W: Write>(
+    mut writer: csv::Writer<W>,
+    items: &[models::Item],
+    groups: &[models::Group],
+    lookups: &Lookups,
+    header: Option<Vec<String>>,
+    skip_ids: &BTreeSet<String>,
+) -> Result<usize> {
+    let group_mapping = group_title_map(groups);
+    let column_ids = match header {
+        Some(header) => header[CSV_FIXED_HEADER.len()..].to_vec(),
+        None => {
+            let column_ids = collect_column_ids(items);
+            let mut header: Vec<String> = CSV_FIXED_HEADER
+                .iter()
+                .map(|name| name.to_string())
+                .collect();
+            header.extend(column_ids.iter().cloned());
+            writer.write_record(&header)?;
+            column_ids
+        }
+    };
+
+    let mut written = 0;
+    for item in items {
+        if skip_ids.contains(&item.id) {
+            continue;
+        }
+        let group_name = group_mapping
+            .get(item.group.id.as_str())
+            .unwrap_or(&"Unknown");
+
+        let mut record = vec![item.id.clone(), item.name.clone(), group_name.to_string()];
+        for column_id in &column_ids {
+            record.push(display_column(item, column_id, lookups));
+        }
+        writer.write_record(&record)?;
+        written += 1;
+    }
+
+    writer.flush()?;
+    Ok(written)
+}
+
+/// The header of a CSV file written by `query --csv` and the item IDs in it, no header when
+/// the file is missing or empty.
+fn read_csv_header_and_ids(path: &str) -> Result<(Option<Vec<String>>, BTreeSet<String>)> {
+    let mut ids = BTreeSet::new();
+    if !Path::new(path).exists() {
+        return Ok((None, ids));
+    }
+    let mut reader =
+        csv::Reader::from_path(path).map_err(|e| anyhow!("Failed to read {}: {}", path, e))?;
+    let header: Vec<String> = reader.headers()?.iter().map(str::to_string).collect();
+    if header.is_empty() {
+        return Ok((None, ids));
+    }
+    if !header.starts_with(&CSV_FIXED_HEADER.map(str::to_string)) {
+        return Err(CliError::InvalidInput(format!(
+            "{} doesn't start with the {} columns of a `query --csv` export, can't append to it",
+            path,
+            CSV_FIXED_HEADER.join(", ")
+        ))
+        .into());
+    }
+    for record in reader.records() {
+        if let Some(id) = record?.get(0) {
+            ids.insert(id.to_string());
+        }
+    }
+    Ok((Some(header), ids))
+}
+
+fn print_items_json(
+    out: &mut dyn Write,
+    items: &[models::Item],
+    groups: &[models::Group],
+    lookups: &Lookups,
+) -> Result<()> {
+    let group_mapping = group_title_map(groups);
+
+    let decoded_items: Vec<DecodedItem> = items
+        .iter()
+        .map(|item| decoded_item(item, &group_mapping, lookups))
+        .collect();
+
+    writeln!(out, "{}", serde_json::to_string_pretty(&decoded_items)?)?;
+    Ok(())
+}
+
+fn decoded_item(
+    item: &models::Item,
+    group_mapping: &HashMap<&str, &str>,
+    lookups: &Lookups,
+) -> DecodedItem {
+    DecodedItem {
+        id: item.id.clone(),
+        name: item.name.clone(),
+        group_id: item.group.id.clone(),
+        group: group_mapping
+            .get(item.group.id.as_str())
+            .unwrap_or(&"Unknown")
+            .to_string(),
+        column_values: item
+            .column_values
+            .iter()
+            .map(|column| (column.id.clone(), display_column_value(column, lookups)))
+            .collect(),
+    }
+}
+
+/// Create a mapping from group ID to group title
+fn group_title_map(groups: &[models::Group]) -> HashMap<&str, &str> {
+    groups
+        .iter()
+        .map(|group| (group.id.as_str(), group.title.as_str()))
+        .collect()
+}
+
+/// Collect all unique column IDs, in the order they first appear
+fn collect_column_ids(items: &[models::Item]) -> Vec<String> {
+    let mut column_ids = Vec::new();
+    for item in items {
+        for column in &item.column_values {
+            if !column_ids.contains(&column.id) {
+                column_ids.push(column.id.clone());
+            }
+        }
+    }
+    column_ids
+}
+
+/// Decoded value of the given column for an item, empty if the item doesn't have it.
+fn decoded_column(item: &models::Item, column_id: &str) -> String {
+    item.column_values
+        .iter()
+        .find(|c| c.id == column_id)
+        .and_then(|column_value| column_value.value.as_deref())
+        .map(decode_column_value)
+        .unwrap_or_default()
+}
+
+/// Like `decoded_column`, but with IDs and indices replaced by the names in `lookups` where known.
+fn display_column(item: &models::Item, column_id: &str, lookups: &Lookups) -> String {
+    item.column_values
+        .iter()
+        .find(|c| c.id == column_id)
+        .map(|column_value| display_column_value(column_value, lookups))
+        .unwrap_or_default()
+}
+
+fn display_column_value(column: &models::ColumnValue, lookups: &Lookups) -> String {
+    match lookups.transforms.get(&column.id) {
+        Some(ColumnTransform::Raw) => column.value.clone().unwrap_or_default(),
+        // Columns without an address, e.g. not link columns after all, are shown as usual
+        Some(ColumnTransform::Url) => column
+            .value
+            .as_deref()
+            .and_then(|value| serde_json::from_str::<serde_json::Value>(value).ok())
+            .and_then(|parsed| parsed.get("url")?.as_str().map(str::to_string))
+            .unwrap_or_else(|| labelled_column_value(column, lookups)),
+        Some(ColumnTransform::FirstWord) => labelled_column_value(column, lookups)
+            .split_whitespace()
+            .next()
+            .unwrap_or_default()
+            .to_string(),
+        None => labelled_column_value(column, lookups),
+    }
+}
+
+/// The decoded value with status indices and person IDs replaced by their names where known.
+fn labelled_column_value(column: &models::ColumnValue, lookups: &Lookups) -> String {
+    let value = column.value.as_deref();
+    let parsed = value.and_then(|value| serde_json::from_str::<serde_json::Value>(value).ok());
+    if parsed.is_none() && value.is_some_and(|value| value.starts_with(['{', '['])) {
+        warn!(
+            "Could not decode the value of column {}, showing it as-is",
+            column.id
+        );
+    }
+
+    // Status columns store the label index, show the label itself when the board told us
+    if let (Some(labels), Some(index)) = (
+        lookups.status_labels.get(&column.id),
+        parsed
+            .as_ref()
+            .and_then(|parsed| parsed.get("index"))
+            .and_then(|v| v.as_i64()),
+    ) {
+        if let Some(label) = labels.get(&index) {
+            return label.clone();
+        }
+    }
+
+    if let Some(persons) = parsed
+        .as_ref()
+        .and_then(|parsed| parsed.get("personsAndTeams"))
+        .and_then(|v| v.as_array())
+    {
+        // Fall back to the raw ID for anyone we couldn't resolve
+        let names: Vec<String> = persons
+            .iter()
+            .filter_map(|p| p.get("id").and_then(|v| v.as_i64()))
+            .map(|id| {
+                lookups
+                    .people
+                    .get(&id)
+                    .cloned()
+                    .unwrap_or_else(|| id.to_string())
+            })
+            .collect();
+        return names.join(", ");
+    }
+
+    value.map(decode_column_value).unwrap_or_default()
+}
+
+/// Index -> label maps for every status column whose settings could be parsed, keyed by column ID
+fn status_labels(columns: &[models::BoardColumn]) -> HashMap<String, HashMap<i64, String>> {
+    columns
+        .iter()
+        .filter(|column| column.column_type == "status")
+        .filter_map(|column| {
+            let settings: serde_json::Value =
+                serde_json::from_str(column.settings_str.as_deref()?).ok()?;
+            let labels = settings
+                .get("labels")?
+                .as_object()?
+                .iter()
+                .filter_map(|(index, label)| {
+                    Some((index.parse::<i64>().ok()?, label.as_str()?.to_string()))
+                })
+                .collect();
+            Some((column.id.clone(), labels))
+        })
+        .collect()
+}
+
+/// Person IDs referenced by any `personsAndTeams` column value, deduplicated
+fn collect_person_ids(items: &[models::Item]) -> BTreeSet<i64> {
+    items
+        .iter()
+        .flat_map(|item| &item.column_values)
+        .filter_map(|column| column.value.as_deref())
+        .flat_map(person_ids)
+        .collect()
+}
+
+/// The people (not teams) in a `personsAndTeams` column value, empty for any other value.
+fn person_ids(value: &str) -> Vec<i64> {
+    let Ok(parsed) = serde_json::from_str::<serde_json::Value>(value) else {
+        return Vec::new();
+    };
+    let Some(entries) = parsed.get("personsAndTeams").and_then(|v| v.as_array()) else {
+        return Vec::new();
+    };
+    entries
+        .iter()
+        .filter(|p| p.get("kind").and_then(|v| v.as_str()).unwrap_or("person") == "person")
+        .filter_map(|p| p.get("id").and_then(|v| v.as_i64()))
+        .collect()
+}
+
+/// Whether the configured user is among the people in the item's person column.
+fn is_assigned_to_user(config: &Config, item: &models::Item) -> bool {
+    item.column_values
+        .iter()
+        .find(|column| column.id == config.columns.person)
+        .and_then(|column| column.value.as_deref())
+        .is_some_and(|value| {
+            person_ids(value)
+                .iter()
+                .any(|id| u64::try_from(*id) == Ok(config.user_id))
+        })
+}
+
+/// Fetch what's needed to show names instead of IDs; failures only cost readability.
+async fn resolve_lookups(
+    api: &MondayClient,
+    items: &[models::Item],
+    columns: &[models::BoardColumn],
+) -> Lookups {
+    let mut lookups = Lookups {
+        status_labels: status_labels(columns),
+        transforms: api.config().transforms.clone(),
+        ..Default::default()
+    };
+
+    let person_ids = collect_person_ids(items);
+    if !person_ids.is_empty() {
+        match api.get_user_names(&person_ids).await {
+            Ok(people) => lookups.people = people,
+            Err(e) => warn!("Could not resolve person names, showing IDs: {}", e),
+        }
+    }
+
+    lookups
+}
+
+/// Fill the client, work item and activity not given on the command line with the ones of the
+/// last `add` on the board.
+fn fill_last_used(board_id: u64, fields: &mut prompt::AddFields, interactive: bool) -> Result<()> {
+    let last = state::last_used(board_id);
+    fields.client = fields.client.take().or(last.client);
+    fields.wi = fields.wi.take().or(last.wi);
+    fields.activity = fields.activity.take().or(last.activity);
+
+    // Whatever is still missing is asked for in interactive mode
+    if !interactive {
+        for (value, flag) in [
+            (&fields.client, "--client"),
+            (&fields.wi, "--wi"),
+            (&fields.activity, "--activity"),
+        ] {
+            if value.is_none() {
+                return Err(CliError::InvalidInput(format!(
+                    "No last-used value for {} on board {} yet, pass it explicitly",
+                    flag, board_id
+                ))
+                .into());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Fill the activity, client and work item that weren't given from the named template, failing
+/// if one is still missing unless `leave_missing` is set.
+fn apply_template(
+    config: &Config,
+    name: &str,
+    fields: &mut prompt::AddFields,
+    leave_missing: bool,
+) -> Result<()> {
+    let template = config.template(name).ok_or_else(|| {
+        let names: Vec<&str> = config
+            .templates
+            .iter()
+            .map(|template| template.name.as_str())
+            .collect();
+        CliError::InvalidInput(match names.as_slice() {
+            [] => format!(
+                "Unknown template `{}`, no [[templates]] are configured",
+                name
+            ),
+            names => format!(
+                "Unknown template `{}`, configured templates are: {}",
+                name,
+                names.join(", ")
+            ),
+        })
+    })?;
+    fields.activity = fields.activity.take().or(template.activity.clone());
+    fields.client = fields.client.take().or(template.client.clone());
+    fields.wi = fields.wi.take().or(template.wi.clone());
+
+    if !leave_missing {
+        for (value, flag) in [
+            (&fields.activity, "--activity"),
+            (&fields.client, "--client"),
+            (&fields.wi, "--wi"),
+        ] {
+            if value.is_none() {
+                return Err(CliError::InvalidInput(format!(
+                    "Template `{}` doesn't set {}, pass it explicitly",
+                    name, flag
+                ))
+                .into());
+            }
+        }
+    }
+    Ok(())
+}
+
+fn missing_name_error() -> anyhow::Error {
+    CliError::InvalidInput(
+        "--name is required unless a name template is set with --name-template or `name_template` in the config file".to_string(),
+    )
+    .into()
+}
+
+/// Create the item described by the `add` arguments, prompting for missing fields with
+/// `--interactive`. Returns `None` on a dry run.
+async fn add_item(api: &MondayClient, add_matches: &ArgMatches) -> Result<Option<CreatedItem>> {
+    let field = |name: &str| add_matches.get_one::<String>(name).cloned();
+    let extra_columns = column_sets(add_matches)?;
+    let mut fields = prompt::AddFields {
+        year: field("year"),
+        name: field("name"),
+        activity: field("activity"),
+        date: field("date"),
+        client: field("client"),
+        wi: field("wi"),
+        hours: field("hours"),
+    };
+    let board_id = api.config().board_id;
+    let interactive = add_matches.get_flag("interactive");
+    if let Some(name) = add_matches.get_one::<String>("template") {
+        // --use-last and --interactive fill in whatever the template leaves out
+        let leave_missing = interactive || add_matches.get_flag("use-last");
+        apply_template(api.config(), name, &mut fields, leave_missing)?;
+    }
+    if add_matches.get_flag("use-last") {
+        fill_last_used(board_id, &mut fields, interactive)?;
+    }
+    let template = add_matches
+        .get_one::<String>("name-template")
+        .or(api.config().name_template.as_ref());
+    if interactive {
+        prompt::fill_add_fields(api.config(), &mut fields, template.map(String::as_str))?;
+    }
+    // Normalized first so the name template and the daily check see the ISO date
+    match fields.date.as_mut() {
+        Some(date) => *date = parse_date(date)?.to_string(),
+        None => {
+            let today = api.config().today();
+            output::info(format!(
+                "Using today's date {} ({})",
+                today,
+                api.config().timezone_name()
+            ));
+            fields.date = Some(today.to_string());
+        }
+    }
+    if fields.hours.is_none() {
+        let hours = api.config().default_hours();
+        output::info(format!("Logging the default of {} hours", hours));
+        fields.hours = Some(hours.to_string());
+    }
+    if fields.name.is_none() {
+        let template = template.ok_or_else(missing_name_error)?;
+        fields.name = Some(fields.render_name(template)?);
+    }
+    // Without --interactive clap already insisted on every field but the name, date and hours
+    let year = fields.year.as_deref().unwrap();
+    let name = fields.name.as_deref().unwrap();
+    let activity = fields.activity.as_deref().unwrap();
+    let date = fields.date.as_deref().unwrap();
+    let client_name = fields.client.as_deref().unwrap();
+    let wi = fields.wi.as_deref().unwrap();
+    let hours = fields.hours.as_deref().unwrap();
+    let create_group = add_matches.get_flag("create-group");
+
+    let created = add_board_item(
+        api,
+        year,
+        name,
+        activity,
+        date,
+        client_name,
+        wi,
+        hours,
+        create_group,
+        add_matches.get_flag("allow-duplicate"),
+        &extra_columns,
+    )
+    .await?;
+    let Some(id) = created else {
+        return Ok(None);
+    };
+    if add_matches.get_flag("verify") {
+        verify_assignment(api, &id).await;
+    }
+    let last_used = state::LastUsed {
+        client: Some(client_name.to_string()),
+        wi: Some(wi.to_string()),
+        activity: Some(activity.to_string()),
+    };
+    if let Err(e) = state::store_last_used(board_id, last_used) {
+        warn!("Could not remember the values of this add: {}", e);
+    }
+    check_daily_hours(api, date).await;
+    Ok(Some(CreatedItem {
+        id,
+        name: name.to_string(),
+    }))
+}
+
+/// The error that ended a `--json` command as a JSON object, with the individual GraphQL errors
+/// where the API sent some.
+fn error_json(error: &anyhow::Error) -> serde_json::Value {
+    let mut object = json!({ "error": format!("{:#}", error) });
+    if let Some(CliError::GraphQL(errors)) = error.downcast_ref::<CliError>() {
+        object["graphql_errors"] = errors
+            .iter()
+            .map(|error| json!({ "message": error.message, "code": error.code() }))
+            .collect();
+    }
+    object
+}
+
+/// Create an item, returning its ID, or `None` for a dry run where nothing was sent.
+#[allow(clippy::too_many_arguments)]
+async fn add_board_item(
+    api: &MondayClient,
+    year: &str,
+    name: &str,
+    activity: &str,
+    date: &str,
+    client_name: &str,
+    wi: &str,
+    hours: &str,
+    create_group: bool,
+    allow_duplicate: bool,
+    extra_columns: &serde_json::Map<String, serde_json::Value>,
+) -> Result<Option<String>> {
+    let config = api.config();
 
-    let config_path = matches.get_one::<String>("config").unwrap();
-    let config = Config::from_file(config_path)?;
+    require_text("Item name", name)?;
+    require_text("Client", client_name)?;
+    require_text("Work item", wi)?;
 
-    let client = Client::new();
+    // Map activity text to integer value
+    let activity_value = config.activity_value(activity)?;
 
-    match matches.subcommand() {
-        Some(("query", query_matches)) => {
-            let limit = query_matches.get_one::<String>("limit").unwrap();
-            extract_board_items(&client, &config, limit).await?;
+    // Validate the date and hours locally rather than letting Monday reject them
+    let date = parse_date(date)?.to_string();
+    let hours = parse_hours(hours)?;
+
+    info!("Looking up group ID for year: {}", year);
+    let groups = api.get_groups_including(year).await?;
+    let group_id = match group_by_title(&groups, year) {
+        Ok(group) => {
+            info!("Found group ID: {} for year: {}", group.id, year);
+            group.id.clone()
         }
-        Some(("add", add_matches)) => {
-            let year = add_matches.get_one::<String>("year").unwrap();
-            let name = add_matches.get_one::<String>("name").unwrap();
-            let activity = add_matches.get_one::<String>("activity").unwrap();
-            let date = add_matches.get_one::<String>("date").unwrap();
-            let client_name = add_matches.get_one::<String>("client").unwrap();
-            let wi = add_matches.get_one::<String>("wi").unwrap();
-            let hours = add_matches.get_one::<String>("hours").unwrap();
-
-            add_board_item(
-                &client,
-                &config,
-                year,
+        Err(_) if create_group => {
+            info!("No group found for year {}, creating it", year);
+            match api.create_group(year).await? {
+                Some(group_id) => {
+                    output::success(format!(
+                        "Group {} created successfully! ID: {}",
+                        year, group_id
+                    ));
+                    group_id
+                }
+                // Lets the dry run still show the item request that would follow
+                None => "<new group ID>".to_string(),
+            }
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    if !allow_duplicate {
+        if let Some(existing) = find_duplicate(api, &group_id, name, &date, client_name).await? {
+            return Err(anyhow!(
+                "An item named \"{}\" for {} on {} already exists in group {} (ID: {}), pass --allow-duplicate to create it anyway",
                 name,
-                activity,
-                date,
                 client_name,
-                wi,
-                hours,
-            )
-            .await?;
-        }
-        _ => {
-            println!("No subcommand provided. Use --help for usage information.");
+                date,
+                year,
+                existing.id
+            ));
         }
     }
 
-    Ok(())
-}
+    // Create column values JSON using user_id and the column IDs from config
+    let columns = &config.columns;
+    let mut column_values = json!({
+        columns.person.as_str(): json!({
+            "personsAndTeams": [{
+                "id": config.user_id,
+                "kind": "person"
+            }]
+        }),
+        columns.status.as_str(): json!({
+            "index": activity_value
+        }),
+        columns.date.as_str(): json!({
+            "date": date
+        }),
+        columns.client.as_str(): client_name,
+        columns.work_item.as_str(): wi,
+        columns.hours.as_str(): hours.to_string()
+    });
+    // Values from --set win over the generated ones
+    if let serde_json::Value::Object(values) = &mut column_values {
+        values.extend(extra_columns.clone());
+    }
 
-async fn extract_board_items(client: &Client, config: &Config, limit: &str) -> Result<()> {
-    // Build the GraphQL query to get board structure including groups
-    let board_structure_query = format!(
-        r#"
-        query GetBoardStructure {{
-            boards(ids: "{}") {{
-                name
-                id
-                groups {{
-                    id
-                    title
-                }}
-                items_page(limit: {}) {{
-                    items {{
-                        id
-                        name
-                        group {{
-                            id
-                        }}
-                        column_values {{
-                            id
-                            value
-                        }}
-                    }}
-                }}
-            }}
-        }}
-        "#,
-        config.board_id, limit
-    );
+    info!("Creating new item: {}", name);
+    info!("Activity: {} (index: {})", activity, activity_value);
+    info!("Adding to group ID: {}", group_id);
 
-    let request = GraphQLRequest {
-        query: board_structure_query,
-        variables: Some(serde_json::json!({})),
+    let Some(item) = api
+        .create_item(&group_id, name, &column_values)
+        .await
+        .map_err(|e| explain_column_error(e, &column_values))?
+    else {
+        return Ok(None);
     };
 
-    println!("Sending query to Monday.com API to get board structure...");
+    output::success(format!("Item created successfully! ID: {}", item.id));
+    Ok(Some(item.id))
+}
 
-    let response_text = client
-        .post(MONDAY_API_URL)
-        .header("Authorization", &config.api_key)
-        .header("Content-Type", "application/json")
-        .json(&request)
-        .send()
-        .await?
-        .text()
-        .await?;
+/// Warn unless the created item's person column includes user_id, since a wrong user_id
+/// doesn't make the create fail but assigns the item to someone else or to nobody.
+async fn verify_assignment(api: &MondayClient, item_id: &str) {
+    let config = api.config();
+    let columns = [config.columns.person.clone()];
+    let item = match api.get_item(item_id, Some(&columns)).await {
+        Ok(details) => details.item,
+        Err(e) => {
+            output::warning(format!("Could not verify the person column: {}", e));
+            return;
+        }
+    };
+    if is_assigned_to_user(config, &item) {
+        output::success(format!("Verified: assigned to user {}", config.user_id));
+        return;
+    }
+    let found: Vec<String> = item
+        .column_values
+        .iter()
+        .find(|column| column.id == config.columns.person)
+        .and_then(|column| column.value.as_deref())
+        .map(person_ids)
+        .unwrap_or_default()
+        .iter()
+        .map(|id| id.to_string())
+        .collect();
+    output::warning(format!(
+        "The person column ({}) of item {} doesn't include user_id {} but {}, check user_id and columns.person in the config",
+        config.columns.person,
+        item_id,
+        config.user_id,
+        if found.is_empty() {
+            "nobody".to_string()
+        } else {
+            found.join(", ")
+        }
+    ));
+}
 
-    println!("API Response received");
+/// Put what Monday's `error_data` says about rejected column values in front of the error, since
+/// its message alone is only a generic "invalid value".
+fn explain_column_error(error: anyhow::Error, column_values: &serde_json::Value) -> anyhow::Error {
+    let Some(CliError::GraphQL(errors)) = error.downcast_ref::<CliError>() else {
+        return error;
+    };
+    let mismatches: Vec<String> = errors
+        .iter()
+        .filter_map(|error| error.column_mismatch(column_values))
+        .collect();
+    if mismatches.is_empty() {
+        return error;
+    }
+    error.context(mismatches.join("; "))
+}
 
-    // Parse the response
-    match serde_json::from_str::<GraphQLResponse<models::BoardStructureResponse>>(&response_text) {
-        Ok(response) => {
-            if let Some(errors) = response.errors {
-                for error in errors {
-                    eprintln!("GraphQL Error: {}", error.message);
-                }
-                return Ok(());
-            }
+/// An item in the group with the same name, date and client, e.g. left over from an add whose
+/// response was lost.
+async fn find_duplicate(
+    api: &MondayClient,
+    group_id: &str,
+    name: &str,
+    date: &str,
+    client_name: &str,
+) -> Result<Option<models::Item>> {
+    let config = api.config();
+    let items = api.get_items_on_date(date).await?;
+    Ok(items.into_iter().find(|item| {
+        item.group.id == group_id
+            && item.name == name
+            && decoded_column(item, &config.columns.date) == date
+            && decoded_column(item, &config.columns.client) == client_name
+    }))
+}
 
-            if let Some(data) = response.data {
-                if let Some(board) = data.boards.first() {
-                    // Print groups information
-                    print_groups_table(&board.groups);
+/// Add one item per day of the week starting at `start`, Monday to Friday unless weekends are
+/// included, and print how each day went.
+async fn log_week(
+    api: &MondayClient,
+    start: NaiveDate,
+    fields: &prompt::AddFields,
+    name_template: Option<&str>,
+    include_weekends: bool,
+    create_group: bool,
+) -> Result<()> {
+    if start.weekday() != Weekday::Mon {
+        return Err(CliError::InvalidInput(format!(
+            "--start {} is a {}, expected a Monday",
+            start,
+            start.format("%A")
+        ))
+        .into());
+    }
+    // Fail once up front rather than once per day
+    api.config()
+        .activity_value(fields.activity.as_deref().unwrap())?;
+    parse_hours(fields.hours.as_deref().unwrap())?;
 
-                    // Print items information
-                    print_items_table(&board.items_page.items, &board.groups);
-                } else {
-                    println!("No boards found with the specified ID.");
-                }
-            } else {
-                println!("No data returned from API.");
+    let days = if include_weekends { 7 } else { 5 };
+    let mut results = Vec::new();
+    for offset in 0..days {
+        let date = (start + Days::new(offset)).to_string();
+        let mut day = fields.clone();
+        day.date = Some(date.clone());
+        let outcome = match (&day.name, name_template) {
+            (Some(name), _) => Ok(name.clone()),
+            (None, Some(template)) => day.render_name(template),
+            (None, None) => Err(missing_name_error()),
+        };
+        let outcome = match outcome {
+            Ok(name) => {
+                add_board_item(
+                    api,
+                    day.year.as_deref().unwrap(),
+                    &name,
+                    day.activity.as_deref().unwrap(),
+                    &date,
+                    day.client.as_deref().unwrap(),
+                    day.wi.as_deref().unwrap(),
+                    day.hours.as_deref().unwrap(),
+                    create_group,
+                    // Only `add` checks for duplicates, to keep the requests per item down
+                    true,
+                    &serde_json::Map::new(),
+                )
+                .await
             }
-        }
-        Err(e) => {
-            eprintln!("Failed to parse response: {}", e);
-            eprintln!("Raw response was: {}", response_text);
+            Err(e) => Err(e),
+        };
+        results.push((date, outcome));
+    }
 
-            // Try to parse as generic JSON to see what we got
-            if let Ok(raw_json) = serde_json::from_str::<serde_json::Value>(&response_text) {
-                eprintln!("Parsed as generic JSON: {:#?}", raw_json);
+    println!();
+    println!("Week summary:");
+    let mut created = 0;
+    for (date, outcome) in &results {
+        match outcome {
+            Ok(Some(id)) => {
+                created += 1;
+                println!("  ✅ {}: ID {}", date, id);
             }
+            Ok(None) => println!("  🔍 {}: dry run", date),
+            Err(e) => println!("  ❌ {}: failed: {:#}", date, e),
         }
     }
+    println!("Created {} of {} items", created, results.len());
 
+    let failed = results
+        .iter()
+        .filter(|(_, outcome)| outcome.is_err())
+        .count();
+    if failed > 0 {
+        return Err(anyhow!("{} of {} items failed", failed, results.len()));
+    }
     Ok(())
 }
 
-fn print_groups_table(groups: &[models::Group]) {
-    if groups.is_empty() {
-        println!("No groups found in the board.");
-        return;
+/// Warn when the hours logged on `date` add up to more than `max_daily_hours`.
+///
+/// Only a sanity check after the item was created, so a failed lookup is just a warning.
+async fn check_daily_hours(api: &MondayClient, date: &str) {
+    let config = api.config();
+    let items = match api.get_items_on_date(date).await {
+        Ok(items) => items,
+        Err(e) => {
+            warn!("Could not check the hours logged on {}: {}", date, e);
+            return;
+        }
+    };
+
+    // The API already filters by date, checking again guards against boards where it can't
+    let total: f64 = items
+        .iter()
+        .filter(|item| decoded_column(item, &config.columns.date) == date)
+        .filter_map(|item| {
+            decoded_column(item, &config.columns.hours)
+                .trim()
+                .parse::<f64>()
+                .ok()
+        })
+        .sum();
+    info!("{} hours logged on {}", total, date);
+
+    let max = config.max_daily_hours();
+    if total > max {
+        output::warning(format!(
+            "{} hours are now logged on {}, more than the daily maximum of {}",
+            total, date, max
+        ));
     }
+}
 
-    let mut table = Table::new();
-    table.add_row(row!["Group ID", "Group Title"]);
+async fn move_board_item(api: &MondayClient, item_id: &str, year: &str) -> Result<()> {
+    let item = api.get_item_summary(item_id).await?;
+    let group = api.find_group_by_title(year).await?;
 
-    for group in groups {
-        table.add_row(row![group.id, group.title]);
+    info!(
+        "Moving item {} from group {} to group ID: {}",
+        item.name, item.group.title, group.id
+    );
+
+    if let Some(moved) = api.move_item_to_group(item_id, &group.id).await? {
+        output::success(format!(
+            "Moved {} (ID: {}) from group {} to group {}",
+            moved.name, moved.id, item.group.title, moved.group.title
+        ));
     }
 
-    println!("Found {} groups:", groups.len());
-    table.printstd();
-    println!(); // Add empty line for separation
+    Ok(())
 }
 
-fn print_items_table(items: &[models::Item], groups: &[models::Group]) {
-    if items.is_empty() {
-        println!("No items found in the board.");
-        return;
+/// Set the hours column of a single item.
+async fn update_item_hours(api: &MondayClient, item_id: &str, hours: &str) -> Result<()> {
+    let hours = parse_hours(hours)?;
+    let hours_column = &api.config().columns.hours;
+
+    info!("Setting {} of item {} to {}", hours_column, item_id, hours);
+
+    if let Some(item) = api
+        .change_simple_column_value(item_id, hours_column, &hours.to_string())
+        .await?
+    {
+        output::success(format!(
+            "Updated {} (ID: {}) to {} hours",
+            item.name, item.id, hours
+        ));
     }
 
-    // Create a mapping from group ID to group title
-    let group_mapping: std::collections::HashMap<&str, &str> = groups
-        .iter()
-        .map(|group| (group.id.as_str(), group.title.as_str()))
-        .collect();
+    Ok(())
+}
 
-    // Collect all unique column IDs
-    let mut column_ids = Vec::new();
-    for item in items {
-        for column in &item.column_values {
-            if !column_ids.contains(&column.id) {
-                column_ids.push(column.id.clone());
-            }
-        }
+/// Give an item a new name, which Monday stores in the special `name` column.
+async fn rename_item(api: &MondayClient, item_id: &str, name: &str) -> Result<()> {
+    require_text("Item name", name)?;
+    let name = name.trim();
+    let before = api.get_item_summary(item_id).await?;
+
+    info!("Renaming item {} to {}", item_id, name);
+
+    if let Some(item) = api
+        .change_simple_column_value(item_id, "name", name)
+        .await?
+    {
+        output::success(format!(
+            "Renamed item {} from \"{}\" to \"{}\"",
+            item.id, before.name, item.name
+        ));
     }
 
-    // Create table with headers
-    let mut table = Table::new();
+    Ok(())
+}
 
-    // Build headers row
-    let mut header_cells = vec![
-        prettytable::Cell::new("ID"),
-        prettytable::Cell::new("Name"),
-        prettytable::Cell::new("Group"),
-    ];
-    for column_id in &column_ids {
-        header_cells.push(prettytable::Cell::new(&format!("Column {}", column_id)));
+/// Set the columns given with `update --set` in a single mutation.
+async fn set_item_columns(
+    api: &MondayClient,
+    item_id: &str,
+    column_values: serde_json::Map<String, serde_json::Value>,
+) -> Result<()> {
+    let column_ids: Vec<String> = column_values.keys().cloned().collect();
+    info!(
+        "Setting columns {} of item {}",
+        column_ids.join(", "),
+        item_id
+    );
+
+    if let Some(item) = api
+        .change_multiple_column_values(item_id, &serde_json::Value::Object(column_values))
+        .await?
+    {
+        output::success(format!(
+            "Updated {} of {} (ID: {})",
+            column_ids.join(", "),
+            item.name,
+            item.id
+        ));
     }
-    table.add_row(prettytable::Row::new(header_cells));
 
-    // Add data rows
-    for item in items {
-        let group_name = group_mapping
-            .get(item.group.id.as_str())
-            .unwrap_or(&"Unknown");
+    Ok(())
+}
 
-        let mut row_cells = vec![
-            prettytable::Cell::new(&item.id),
-            prettytable::Cell::new(&item.name),
-            prettytable::Cell::new(group_name),
-        ];
+/// The column values given with `--set COLUMN_ID=JSON`, by column ID.
+fn column_sets(matches: &ArgMatches) -> Result<serde_json::Map<String, serde_json::Value>> {
+    let mut column_values = serde_json::Map::new();
+    for set in matches.get_many::<String>("set").into_iter().flatten() {
+        let (column_id, value) = set
+            .split_once('=')
+            .filter(|(column_id, _)| !column_id.trim().is_empty())
+            .ok_or_else(|| {
+                CliError::InvalidInput(format!("--set {} should look like COLUMN_ID=JSON", set))
+            })?;
+        let value = serde_json::from_str(value).map_err(|e| {
+            CliError::InvalidInput(format!(
+                "--set {}: the value is not valid JSON: {}",
+                column_id.trim(),
+                e
+            ))
+        })?;
+        column_values.insert(column_id.trim().to_string(), value);
+    }
+    Ok(column_values)
+}
 
-        for column_id in &column_ids {
-            if let Some(column_value) = item.column_values.iter().find(|c| &c.id == column_id) {
-                let display_value = match &column_value.value {
-                    Some(value) => {
-                        // Parse the JSON value if it's a JSON string, otherwise use as-is
-                        if value.starts_with('{') || value.starts_with('[') {
-                            if let Ok(parsed) = serde_json::from_str::<serde_json::Value>(value) {
-                                if let Some(text) = parsed.get("text").and_then(|v| v.as_str()) {
-                                    text.to_string()
-                                } else if let Some(date) =
-                                    parsed.get("date").and_then(|v| v.as_str())
-                                {
-                                    date.to_string()
-                                } else if let Some(ids) =
-                                    parsed.get("ids").and_then(|v| v.as_array())
-                                {
-                                    if ids.is_empty() {
-                                        "".to_string()
-                                    } else {
-                                        let id_strings: Vec<String> = ids
-                                            .iter()
-                                            .filter_map(|v| v.as_i64().map(|id| id.to_string()))
-                                            .collect();
-                                        id_strings.join(", ")
-                                    }
-                                } else if let Some(persons) =
-                                    parsed.get("personsAndTeams").and_then(|v| v.as_array())
-                                {
-                                    if persons.is_empty() {
-                                        "".to_string()
-                                    } else {
-                                        let person_ids: Vec<String> = persons
-                                            .iter()
-                                            .filter_map(|p| {
-                                                p.get("id")
-                                                    .and_then(|v| v.as_i64())
-                                                    .map(|id| id.to_string())
-                                            })
-                                            .collect();
-                                        person_ids.join(", ")
-                                    }
-                                } else if let Some(index) =
-                                    parsed.get("index").and_then(|v| v.as_i64())
-                                {
-                                    index.to_string()
-                                } else {
-                                    // Fallback: just display the raw value
-                                    value.clone()
-                                }
-                            } else {
-                                value.clone()
-                            }
-                        } else {
-                            // Remove quotes from string values
-                            value.trim_matches('"').to_string()
-                        }
-                    }
-                    None => "".to_string(),
-                };
+/// Clone an item, optionally moving the copy to another date.
+async fn duplicate_board_item(api: &MondayClient, item_id: &str, date: Option<&str>) -> Result<()> {
+    let date = date
+        .map(parse_date)
+        .transpose()?
+        .map(|date| date.to_string());
 
-                row_cells.push(prettytable::Cell::new(&display_value));
-            } else {
-                row_cells.push(prettytable::Cell::new(""));
-            }
-        }
+    info!("Duplicating item {}", item_id);
+
+    let new_item = api.duplicate_item(item_id).await?;
+    // The ID of the copy is only known once the duplicate exists
+    let new_id = new_item
+        .as_ref()
+        .map_or("<new item ID>", |item| item.id.as_str());
+
+    if let Some(date) = date {
+        info!("Setting date of item {} to {}", new_id, date);
+        let date_column = &api.config().columns.date;
+        api.change_column_value(new_id, date_column, &json!({ "date": date }))
+            .await?;
+    }
 
-        table.add_row(prettytable::Row::new(row_cells));
+    if let Some(new_item) = &new_item {
+        output::success(format!(
+            "Item duplicated successfully! ID: {} ({})",
+            new_item.id, new_item.name
+        ));
     }
 
-    println!("Found {} items:", items.len());
-    table.printstd();
+    Ok(())
 }
 
-async fn add_board_item(
-    client: &Client,
-    config: &Config,
-    year: &str,
-    name: &str,
-    activity: &str,
-    date: &str,
-    client_name: &str,
-    wi: &str,
-    hours: &str,
-) -> Result<()> {
-    // Map activity text to integer value
-    let activity_value = match activity.to_lowercase().as_str() {
-        "vacation" => 0,
-        "billable" => 1,
-        "holding" => 2,
-        "education" => 3,
-        "work_reduction" => 4,
-        "tbd" => 5,
-        "holiday" => 6,
-        "" => 7,
-        "illness" => 8,
-        _ => {
-            eprintln!("❌ Invalid activity type: {}", activity);
-            eprintln!(
-                "Valid activity types are: vacation, billable, holding, education, work_reduction, tbd, holiday, illness"
+/// Delete the items one after another after a single confirmation, then list how each went.
+///
+/// Items that can't be looked up are reported as failed without asking about them.
+async fn delete_items(api: &MondayClient, item_ids: &[&str], force: bool) -> Result<()> {
+    let mut unique: Vec<&str> = Vec::new();
+    for id in item_ids {
+        if !unique.contains(id) {
+            unique.push(id);
+        }
+    }
+    if unique.is_empty() {
+        return Err(CliError::InvalidInput("No item IDs given".to_string()).into());
+    }
+
+    let mut results: Vec<(String, Result<Option<String>>)> = Vec::new();
+    let mut found = Vec::new();
+    for id in &unique {
+        match api.get_item_summary(id).await {
+            Ok(item) => found.push(item),
+            Err(e) => results.push((id.to_string(), Err(e))),
+        }
+    }
+
+    if !found.is_empty() {
+        println!("Items to delete:");
+        for item in &found {
+            println!(
+                "  {} (ID: {}) in group {}",
+                item.name, item.id, item.group.title
             );
+        }
+        if !force && !prompt::confirm(&format!("Delete these {} item(s)?", found.len()), "--force")?
+        {
+            output::info("Nothing was deleted");
             return Ok(());
         }
-    };
+    }
 
-    // First, get the board structure to find the group ID for the given year
-    let board_structure_query = format!(
-        r#"
-        query GetBoardGroups {{
-            boards(ids: "{}") {{
-                groups {{
-                    id
-                    title
-                }}
-            }}
-        }}
-        "#,
-        config.board_id
-    );
+    let progress = Progress::bar(found.len() as u64, "Deleting items");
+    for item in found {
+        let outcome = api
+            .delete_item(&item.id)
+            .await
+            .map(|deleted| deleted.map(|_| item.name.clone()));
+        results.push((item.id, outcome));
+        progress.inc(1);
+    }
+    drop(progress);
+    // In the order given, not with the failed lookups first
+    results.sort_by_key(|(id, _)| unique.iter().position(|other| other == id));
 
-    let request = GraphQLRequest {
-        query: board_structure_query,
-        variables: Some(serde_json::json!({})),
-    };
+    println!();
+    println!("Delete summary:");
+    let mut deleted = 0;
+    for (id, outcome) in &results {
+        match outcome {
+            Ok(Some(name)) => {
+                deleted += 1;
+                println!("  ✅ {}: deleted {}", id, name);
+            }
+            Ok(None) => println!("  🔍 {}: dry run", id),
+            Err(e) => println!("  ❌ {}: failed: {:#}", id, e),
+        }
+    }
+    println!("Deleted {} of {} items", deleted, results.len());
 
-    println!("Looking up group ID for year: {}", year);
+    let failed = results
+        .iter()
+        .filter(|(_, outcome)| outcome.is_err())
+        .count();
+    if failed > 0 {
+        return Err(anyhow!("{} of {} items failed", failed, results.len()));
+    }
+    Ok(())
+}
 
-    let response_text = client
-        .post(MONDAY_API_URL)
-        .header("Authorization", &config.api_key)
-        .header("Content-Type", "application/json")
-        .json(&request)
-        .send()
-        .await?
-        .text()
-        .await?;
+/// Print every accepted activity keyword with the status index it maps to.
+fn print_activities(config: &Config) {
+    let mut table = Table::new();
+    table.add_row(row!["Activity", "Index", "Source"]);
+    for keyword in config.activity_keywords() {
+        // Resolved the same way `add` does, so the table can't drift from the actual mapping
+        let Some(index) = config.activity_index(&keyword) else {
+            continue;
+        };
+        let source = if config.activities.contains_key(&keyword) {
+            "config"
+        } else {
+            "built-in"
+        };
+        table.add_row(row![keyword, index, source]);
+    }
+    table.printstd();
+}
 
-    // Parse the response as raw JSON to extract groups
-    let group_id = match serde_json::from_str::<serde_json::Value>(&response_text) {
-        Ok(response_value) => {
-            if let Some(errors) = response_value.get("errors") {
-                if let Some(error_array) = errors.as_array() {
-                    for error in error_array {
-                        if let Some(message) = error.get("message") {
-                            eprintln!("GraphQL Error: {}", message);
-                        }
-                    }
-                }
-                return Ok(());
-            }
-
-            if let Some(data) = response_value.get("data") {
-                if let Some(boards) = data.get("boards") {
-                    if let Some(board_array) = boards.as_array() {
-                        if let Some(board) = board_array.first() {
-                            if let Some(groups) = board.get("groups") {
-                                if let Some(groups_array) = groups.as_array() {
-                                    // Find the group with the matching year
-                                    let mut found_group_id = None;
-                                    for group in groups_array {
-                                        if let (Some(id), Some(title)) =
-                                            (group.get("id"), group.get("title"))
-                                        {
-                                            if let (Some(id_str), Some(title_str)) =
-                                                (id.as_str(), title.as_str())
-                                            {
-                                                if title_str == year {
-                                                    found_group_id = Some(id_str.to_string());
-                                                    break;
-                                                }
-                                            }
-                                        }
-                                    }
-
-                                    match found_group_id {
-                                        Some(id) => {
-                                            println!("Found group ID: {} for year: {}", id, year);
-                                            id
-                                        }
-                                        None => {
-                                            eprintln!("❌ No group found with title: {}", year);
-                                            eprintln!("Available groups:");
-                                            for group in groups_array {
-                                                if let (Some(id), Some(title)) =
-                                                    (group.get("id"), group.get("title"))
-                                                {
-                                                    if let (Some(id_str), Some(title_str)) =
-                                                        (id.as_str(), title.as_str())
-                                                    {
-                                                        eprintln!("  - {}: {}", title_str, id_str);
-                                                    }
-                                                }
-                                            }
-                                            return Ok(());
-                                        }
-                                    }
-                                } else {
-                                    eprintln!("❌ Groups is not an array");
-                                    return Ok(());
-                                }
-                            } else {
-                                eprintln!("❌ No groups field in board");
-                                return Ok(());
-                            }
-                        } else {
-                            eprintln!("❌ No boards found");
-                            return Ok(());
-                        }
-                    } else {
-                        eprintln!("❌ Boards is not an array");
-                        return Ok(());
-                    }
-                } else {
-                    eprintln!("❌ No boards field in data");
-                    return Ok(());
-                }
-            } else {
-                eprintln!("❌ No data in response");
-                return Ok(());
-            }
+/// Reject an empty or whitespace-only text field, which Monday would otherwise accept as is.
+fn require_text(field: &str, value: &str) -> Result<(), CliError> {
+    if value.trim().is_empty() {
+        return Err(CliError::InvalidInput(format!(
+            "{} must not be empty",
+            field
+        )));
+    }
+    Ok(())
+}
+
+/// Parse an hours value, which may be fractional (e.g. 7.5) but not negative.
+fn parse_hours(hours: &str) -> Result<f64> {
+    match hours.trim().parse::<f64>() {
+        Ok(value) if value.is_finite() && value >= 0.0 => Ok(value),
+        _ => Err(CliError::InvalidInput(format!(
+            "Invalid hours: {}, expected a non-negative number (e.g., 8 or 7.5)",
+            hours
+        ))
+        .into()),
+    }
+}
+
+/// Parse a date written in any of the `DATE_FORMATS`, which Monday is then sent as `YYYY-MM-DD`.
+fn parse_date(date: &str) -> Result<NaiveDate> {
+    let accepted: Vec<&str> = DATE_FORMATS.iter().map(|(_, name)| *name).collect();
+    let date = date.trim();
+    if date.is_empty() {
+        return Err(CliError::InvalidInput(format!(
+            "Date must not be empty, accepted formats: {}",
+            accepted.join(", ")
+        ))
+        .into());
+    }
+    DATE_FORMATS
+        .iter()
+        .find_map(|(format, _)| NaiveDate::parse_from_str(date, format).ok())
+        // Rules out two-digit years such as 10/15/24, which would be read as the year 24
+        .filter(|parsed| parsed.year() >= 1000)
+        .ok_or_else(|| {
+            CliError::InvalidInput(format!(
+                "Invalid date: {}, accepted formats: {} (e.g., 2025-09-05)",
+                date,
+                accepted.join(", ")
+            ))
+            .into()
+        })
+}
+
+async fn bulk_add_items(
+    api: &MondayClient,
+    path: &str,
+    skip_invalid: bool,
+    concurrency: usize,
+) -> Result<()> {
+    let config = api.config();
+    let mut reader =
+        csv::Reader::from_path(path).map_err(|e| anyhow!("Failed to open {}: {}", path, e))?;
+
+    // Validate every row before anything is sent, line numbers count the header as line 1
+    let mut valid_rows = Vec::new();
+    let mut invalid_rows = Vec::new();
+    for (index, record) in reader.deserialize::<BulkAddRow>().enumerate() {
+        let line = index + 2;
+        match record
+            .map_err(|e| anyhow!("{}", e))
+            .and_then(|row| validate_bulk_row(config, &row).map(|_| row))
+        {
+            Ok(row) => valid_rows.push((line, row)),
+            Err(e) => invalid_rows.push((line, e)),
         }
-        Err(e) => {
-            eprintln!("Failed to parse group response: {}", e);
-            eprintln!("Raw response was: {}", response_text);
-            return Ok(());
+    }
+
+    if !invalid_rows.is_empty() {
+        output::error(format!(
+            "Found {} invalid rows in {}:",
+            invalid_rows.len(),
+            path
+        ));
+        for (line, error) in &invalid_rows {
+            eprintln!("  - line {}: {}", line, error);
         }
-    };
+        if !skip_invalid {
+            return Err(CliError::InvalidInput(
+                "No items were created, fix the rows above or pass --skip-invalid".to_string(),
+            )
+            .into());
+        }
+        warn!("Skipping invalid rows");
+    }
 
-    // Create column values JSON string using user_id from config
-    let column_values = json!({
-        "person": json!({
-            "personsAndTeams": [{
-                "id": config.user_id.parse::<i64>()?,
-                "kind": "person"
-            }]
-        }),
-        "status": json!({
-            "index": activity_value
-        }),
-        "date4": json!({
-            "date": date
-        }),
-        "text__1": client_name,
-        "text8__1": wi,
-        "numbers__1": hours
-    })
-    .to_string();
-
-    let query = r#"
-        mutation CreateItem($boardId: ID!, $groupId: String!, $itemName: String!, $columnValues: JSON!) {
-            create_item(
-                board_id: $boardId,
-                group_id: $groupId,
-                item_name: $itemName,
-                column_values: $columnValues
-            ) {
-                id
-                name
+    info!("Creating {} items from {}", valid_rows.len(), path);
+
+    // Create up to `concurrency` items at a time, each request still backs off on its own when
+    // rate limited, and remember how each one went
+    let bar = Progress::bar(valid_rows.len() as u64, "Creating items");
+    let progress = &bar;
+    let mut results: Vec<_> = stream::iter(valid_rows.iter().enumerate())
+        .map(|(index, (line, row))| async move {
+            let outcome = add_board_item(
+                api,
+                &row.year,
+                &row.name,
+                &row.activity,
+                &row.date,
+                &row.client,
+                &row.wi,
+                &row.hours,
+                false,
+                true,
+                &serde_json::Map::new(),
+            )
+            .await;
+            progress.inc(1);
+            (index, line, row, outcome)
+        })
+        .buffer_unordered(concurrency)
+        .collect()
+        .await;
+    drop(bar);
+    // Reported in the order of the file, not the order the requests finished in
+    results.sort_by_key(|(index, ..)| *index);
+    let results: Vec<_> = results
+        .into_iter()
+        .map(|(_, line, row, outcome)| (line, row, outcome))
+        .collect();
+
+    println!();
+    println!("Bulk add summary:");
+    let mut created = 0;
+    for (line, row, outcome) in &results {
+        match outcome {
+            Ok(Some(id)) => {
+                created += 1;
+                println!("  ✅ line {}: {} (ID: {})", line, row.name, id);
             }
+            Ok(None) => println!("  🔍 line {}: {} (dry run)", line, row.name),
+            Err(e) => println!("  ❌ line {}: {} failed: {:#}", line, row.name, e),
         }
-    "#;
+    }
+    println!(
+        "Created {} of {} items ({} invalid rows skipped)",
+        created,
+        results.len(),
+        invalid_rows.len()
+    );
 
-    let variables = json!({
-        "boardId": config.board_id,
-        "groupId": group_id,
-        "itemName": name,
-        "columnValues": column_values
-    });
+    let failed = results
+        .iter()
+        .filter(|(_, _, outcome)| outcome.is_err())
+        .count();
+    if failed > 0 {
+        return Err(anyhow!("{} of {} items failed", failed, results.len()));
+    }
+    Ok(())
+}
 
-    let request = GraphQLRequest {
-        query: query.to_string(),
-        variables: Some(variables),
-    };
+fn validate_bulk_row(config: &Config, row: &BulkAddRow) -> Result<()> {
+    if config.activity_index(&row.activity).is_none() {
+        return Err(anyhow!(
+            "Invalid activity type: {}, valid activity types are: {}",
+            row.activity,
+            config.valid_activities()
+        ));
+    }
+    require_text("Item name", &row.name)?;
+    require_text("Client", &row.client)?;
+    require_text("Work item", &row.wi)?;
+    parse_date(&row.date)?;
+    parse_hours(&row.hours)?;
+    Ok(())
+}
 
-    println!("Creating new item: {}", name);
-    println!("Activity: {} (index: {})", activity, activity_value);
-    println!("Adding to group ID: {}", group_id);
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    let response_text = client
-        .post(MONDAY_API_URL)
-        .header("Authorization", &config.api_key)
-        .header("Content-Type", "application/json")
-        .json(&request)
-        .send()
-        .await?
-        .text()
-        .await?;
+    #[test]
+    fn cli_definition_is_consistent() {
+        build_cli().debug_assert();
+    }
 
-    println!("Create item response: {}", response_text);
-
-    // Parse the response
-    match serde_json::from_str::<serde_json::Value>(&response_text) {
-        Ok(response_value) => {
-            if let Some(errors) = response_value.get("errors") {
-                if let Some(error_array) = errors.as_array() {
-                    for error in error_array {
-                        if let Some(message) = error.get("message") {
-                            eprintln!("GraphQL Error: {}", message);
-                        }
-                    }
-                }
-                return Ok(());
-            }
+    #[test]
+    fn groups_sort_years_numerically_before_other_titles() {
+        let titles = |groups: &[models::Group]| -> Vec<String> {
+            groups.iter().map(|group| group.title.clone()).collect()
+        };
+        let mut groups: Vec<models::Group> = ["2025", "Archive", "999", "2024", "backlog"]
+            .iter()
+            .map(|title| models::Group {
+                id: format!("g{}", title),
+                title: title.to_string(),
+            })
+            .collect();
 
-            if let Some(data) = response_value.get("data") {
-                if let Some(create_item) = data.get("create_item") {
-                    if let Some(id) = create_item.get("id") {
-                        println!("✅ Item created successfully! ID: {}", id);
-                    } else {
-                        println!("✅ Item created successfully!");
-                    }
-                } else {
-                    println!("❌ No create_item data in response");
-                }
-            } else {
-                println!("❌ No data in response");
-            }
-        }
-        Err(e) => {
-            eprintln!("Failed to parse create response: {}", e);
-            eprintln!("Raw response was: {}", response_text);
-        }
+        sort_groups(&mut groups, "api");
+        assert_eq!(
+            titles(&groups),
+            ["2025", "Archive", "999", "2024", "backlog"]
+        );
+        sort_groups(&mut groups, "asc");
+        assert_eq!(
+            titles(&groups),
+            ["999", "2024", "2025", "Archive", "backlog"]
+        );
+        sort_groups(&mut groups, "desc");
+        assert_eq!(
+            titles(&groups),
+            ["2025", "2024", "999", "backlog", "Archive"]
+        );
     }
 
-    Ok(())
+    #[test]
+    fn parse_date_accepts_the_alternate_formats() {
+        let expected = NaiveDate::from_ymd_opt(2024, 10, 15).unwrap();
+        assert_eq!(parse_date("2024-10-15").unwrap(), expected);
+        assert_eq!(parse_date("10/15/2024").unwrap(), expected);
+        assert_eq!(parse_date("15.10.2024").unwrap(), expected);
+        assert_eq!(parse_date(" 10/15/2024 ").unwrap(), expected);
+    }
+
+    #[test]
+    fn markdown_cells_escape_pipes_and_newlines() {
+        assert_eq!(markdown_cell("Acme | Corp"), "Acme \\| Corp");
+        assert_eq!(markdown_cell("first\nsecond"), "first<br>second");
+        assert_eq!(markdown_cell("  ↳ 556"), "&nbsp;&nbsp;↳ 556");
+    }
+
+    #[test]
+    fn person_ids_skip_teams_and_other_values() {
+        let value =
+            r#"{"personsAndTeams":[{"id":42,"kind":"person"},{"id":7,"kind":"team"},{"id":9}]}"#;
+        assert_eq!(person_ids(value), [42, 9]);
+        assert!(person_ids(r#"{"text":"Acme"}"#).is_empty());
+        assert!(person_ids("not json").is_empty());
+    }
+
+    #[test]
+    fn transforms_change_how_column_values_are_shown() {
+        let column = |id: &str, value: &str| models::ColumnValue {
+            id: id.to_string(),
+            value: Some(value.to_string()),
+        };
+        let lookups = Lookups {
+            transforms: BTreeMap::from([
+                ("link".to_string(), ColumnTransform::Url),
+                ("raw".to_string(), ColumnTransform::Raw),
+                ("word".to_string(), ColumnTransform::FirstWord),
+            ]),
+            ..Default::default()
+        };
+        let link = r#"{"url":"https://example.com/wi/7","text":"WI-7"}"#;
+
+        assert_eq!(
+            display_column_value(&column("link", link), &lookups),
+            "https://example.com/wi/7"
+        );
+        assert_eq!(display_column_value(&column("raw", link), &lookups), link);
+        assert_eq!(
+            display_column_value(&column("other", link), &lookups),
+            "WI-7"
+        );
+        assert_eq!(
+            display_column_value(&column("word", "\"Acme Corp\""), &lookups),
+            "Acme"
+        );
+    }
+
+    #[test]
+    fn parse_date_lists_the_accepted_formats() {
+        for date in ["15/10/2024", "10/15/24", "2024.10.15", ""] {
+            let error = parse_date(date).unwrap_err().to_string();
+            assert!(
+                error.contains("YYYY-MM-DD, MM/DD/YYYY, DD.MM.YYYY"),
+                "{}: {}",
+                date,
+                error
+            );
+        }
+    }
 }
@@ -1,4 +1,7 @@
-use serde::{Deserialize, Serialize};
+use crate::config::ColumnTransform;
+use log::warn;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::{BTreeMap, HashMap};
 
 #[derive(Debug, Serialize)]
 pub struct GraphQLRequest {
@@ -16,6 +19,146 @@ pub struct GraphQLResponse<T> {
 #[derive(Debug, Deserialize)]
 pub struct GraphQLError {
     pub message: String,
+    #[serde(default)]
+    pub extensions: Option<GraphQLErrorExtensions>,
+}
+
+/// Machine-readable details Monday attaches to an error.
+#[derive(Debug, Deserialize)]
+pub struct GraphQLErrorExtensions {
+    #[serde(default)]
+    pub code: Option<String>,
+    #[serde(default)]
+    pub status_code: Option<u16>,
+    // Free-form context, e.g. the column a value was rejected for
+    #[serde(default)]
+    pub error_data: Option<serde_json::Value>,
+}
+
+/// Broad categories of GraphQL errors that call for different reactions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphQLErrorKind {
+    Authentication,
+    ComplexityBudget,
+    NotFound,
+    Validation,
+    Other,
+}
+
+impl GraphQLError {
+    pub fn code(&self) -> Option<&str> {
+        self.extensions.as_ref()?.code.as_deref()
+    }
+
+    /// Classify the error from its `extensions`, preferring the code over the status code.
+    pub fn kind(&self) -> GraphQLErrorKind {
+        let code = self.code().unwrap_or_default();
+        let status_code = self.extensions.as_ref().and_then(|e| e.status_code);
+        match code {
+            "UserUnauthorizedException" | "USER_UNAUTHORIZED" | "Unauthorized" => {
+                GraphQLErrorKind::Authentication
+            }
+            "ComplexityException" | "COMPLEXITY_BUDGET_EXHAUSTED" | "maxComplexityExceeded" => {
+                GraphQLErrorKind::ComplexityBudget
+            }
+            "ResourceNotFoundException"
+            | "InvalidBoardIdException"
+            | "InvalidItemIdException"
+            | "InvalidGroupIdException" => GraphQLErrorKind::NotFound,
+            "ColumnValueException"
+            | "InvalidColumnIdException"
+            | "InvalidArgumentException"
+            | "GRAPHQL_VALIDATION_FAILED"
+            | "argumentLiteralsIncompatible" => GraphQLErrorKind::Validation,
+            _ => match status_code {
+                Some(401) | Some(403) => GraphQLErrorKind::Authentication,
+                Some(404) => GraphQLErrorKind::NotFound,
+                Some(429) => GraphQLErrorKind::ComplexityBudget,
+                Some(400) | Some(422) => GraphQLErrorKind::Validation,
+                _ => GraphQLErrorKind::Other,
+            },
+        }
+    }
+
+    /// Which column a value was rejected for and what it expects, from the `error_data` of e.g. a
+    /// ColumnValueException: "column numbers__1 expects a number, got 'abc'".
+    ///
+    /// `sent` are the column values of the request, to find the column by its value when
+    /// `error_data` only names the column type.
+    pub fn column_mismatch(&self, sent: &serde_json::Value) -> Option<String> {
+        let data = self.extensions.as_ref()?.error_data.as_ref()?;
+        let field = |names: &[&str]| {
+            names
+                .iter()
+                .find_map(|name| data.get(*name))
+                .map(|value| match value {
+                    serde_json::Value::String(value) => value.clone(),
+                    value => value.to_string(),
+                })
+        };
+        let column_type = field(&["column_type", "columnType"]);
+        let value = field(&["column_value", "columnValue", "value"]);
+        let column_id = field(&["column_id", "columnId"]).or_else(|| {
+            let value = value.as_deref()?;
+            sent.as_object()?
+                .iter()
+                .find(|(_, sent)| match sent {
+                    serde_json::Value::String(sent) => sent == value,
+                    sent => serde_json::from_str::<serde_json::Value>(value)
+                        .is_ok_and(|value| value == **sent),
+                })
+                .map(|(column_id, _)| column_id.clone())
+        });
+
+        let problem = match (column_type, value) {
+            (Some(column_type), Some(value)) => {
+                format!("expects {}, got '{}'", expected_value(&column_type), value)
+            }
+            (Some(column_type), None) => format!("expects {}", expected_value(&column_type)),
+            (None, Some(value)) => format!("rejected '{}'", value),
+            // Not about a column value
+            (None, None) => return None,
+        };
+        Some(match column_id {
+            Some(column_id) => format!("column {} {}", column_id, problem),
+            None => format!("a column {}", problem),
+        })
+    }
+}
+
+/// What a column of the given type accepts, for type names such as "NumericColumn" or "numbers".
+fn expected_value(column_type: &str) -> String {
+    let kind = column_type.trim_end_matches("Column").to_ascii_lowercase();
+    match kind.as_str() {
+        "numeric" | "numbers" => "a number".to_string(),
+        "date" => "a date".to_string(),
+        "status" | "color" => "a status label or index".to_string(),
+        "people" | "person" | "multiple-person" => "people".to_string(),
+        "text" | "longtext" | "long_text" => "text".to_string(),
+        "link" => "a link".to_string(),
+        _ => format!("a {} value", column_type),
+    }
+}
+
+impl GraphQLErrorKind {
+    /// What the user can do about an error of this kind.
+    pub fn hint(self) -> Option<&'static str> {
+        match self {
+            GraphQLErrorKind::Authentication => Some(
+                "Check that the API key is valid and has access to the board (`monday-claim check`)",
+            ),
+            GraphQLErrorKind::ComplexityBudget => Some(
+                "The complexity budget is used up, wait a minute or fetch fewer items with --limit",
+            ),
+            GraphQLErrorKind::NotFound => {
+                Some("Check the board, group or item ID, it may not exist or not be visible to you")
+            }
+            GraphQLErrorKind::Validation => {
+                Some("A value was rejected, check the arguments and the [columns] config")
+            }
+            GraphQLErrorKind::Other => None,
+        }
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -23,49 +166,430 @@ pub struct BoardStructureResponse {
     pub boards: Vec<Board>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct Board {
     pub name: String,
     pub id: String,
     pub groups: Vec<Group>,
+    #[serde(default)]
+    pub columns: Vec<BoardColumn>,
+    // Missing when the items were left out of the query, see `get_board_structure`
+    #[serde(default)]
+    pub items_page: ItemsPage,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct BoardColumn {
+    pub id: String,
+    #[serde(default)]
+    pub title: String,
+    #[serde(rename = "type")]
+    pub column_type: String,
+    // JSON-encoded column settings, e.g. the labels of a status column
+    #[serde(default)]
+    pub settings_str: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BoardItemsResponse {
+    pub boards: Vec<BoardItems>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BoardItems {
     pub items_page: ItemsPage,
 }
 
 #[derive(Debug, Deserialize)]
+pub struct BoardColumnsResponse {
+    pub boards: Vec<BoardColumns>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BoardColumns {
+    pub columns: Vec<BoardColumn>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BoardGroupsResponse {
+    pub boards: Vec<BoardGroups>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BoardGroups {
+    pub groups: Vec<Group>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Group {
     pub id: String,
     pub title: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize, Serialize)]
 pub struct ItemsPage {
+    // Null once the last page has been returned
+    #[serde(default)]
+    pub cursor: Option<String>,
     pub items: Vec<Item>,
 }
 
 #[derive(Debug, Deserialize)]
+pub struct NextItemsPageResponse {
+    pub next_items_page: ItemsPage,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
 pub struct Item {
     pub id: String,
+    #[serde(default)]
     pub name: String,
+    #[serde(default)]
     pub group: GroupReference,
+    // "active", "archived" or "deleted"; only archived items are marked when displayed
+    #[serde(default)]
+    pub state: Option<String>,
+    // Read one by one so a single unexpected column value doesn't fail the whole response
+    #[serde(default, deserialize_with = "tolerant_column_values")]
     pub column_values: Vec<ColumnValue>,
+    // Only fetched for `query --with-subitems`, empty otherwise
+    #[serde(default, deserialize_with = "null_as_empty")]
+    pub subitems: Vec<Item>,
 }
 
-#[derive(Debug, Deserialize)]
+impl Item {
+    pub fn is_archived(&self) -> bool {
+        self.state.as_deref() == Some("archived")
+    }
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
 pub struct GroupReference {
     pub id: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct ColumnValue {
     pub id: String,
-    #[serde(default)]
+    // The JSON text Monday sends, decoded only when displayed, see `decode_column_value`
+    #[serde(default, deserialize_with = "json_text")]
     pub value: Option<String>,
 }
 
+/// Keep every column value that can be read, logging and skipping the rest.
+fn tolerant_column_values<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<ColumnValue>, D::Error> {
+    let raw = Option::<Vec<serde_json::Value>>::deserialize(deserializer)?.unwrap_or_default();
+    Ok(raw
+        .into_iter()
+        .filter_map(|value| {
+            let id = value
+                .get("id")
+                .and_then(serde_json::Value::as_str)
+                .map(str::to_string);
+            serde_json::from_value::<ColumnValue>(value)
+                .map_err(|e| {
+                    warn!(
+                        "Skipping column {} that could not be read: {}",
+                        id.as_deref().unwrap_or("without an ID"),
+                        e
+                    )
+                })
+                .ok()
+        })
+        .collect())
+}
+
+fn null_as_empty<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Item>, D::Error> {
+    Ok(Option::<Vec<Item>>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+/// Accept a column value of any JSON type; values that aren't strings are kept as JSON text.
+fn json_text<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+    Ok(match serde_json::Value::deserialize(deserializer)? {
+        serde_json::Value::Null => None,
+        serde_json::Value::String(text) => Some(text),
+        other => Some(other.to_string()),
+    })
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UsersResponse {
+    pub users: Vec<User>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct User {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MeResponse {
+    pub me: User,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BoardSummaryResponse {
+    pub boards: Vec<BoardSummary>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BoardSummary {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ItemSummaryResponse {
+    pub items: Vec<ItemSummary>,
+}
+
+/// Just enough of an item to describe it, with its group resolved.
+#[derive(Debug, Deserialize)]
+pub struct ItemSummary {
+    pub id: String,
+    pub name: String,
+    pub group: Group,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ItemDetailsResponse {
+    pub items: Vec<ItemDetails>,
+}
+
+/// An item together with the parts of its board needed to display it.
+#[derive(Debug, Deserialize)]
+pub struct ItemDetails {
+    #[serde(flatten)]
+    pub item: Item,
+    pub board: ItemBoard,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ItemBoard {
+    pub groups: Vec<Group>,
+    #[serde(default)]
+    pub columns: Vec<BoardColumn>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MoveItemResponse {
+    pub move_item_to_group: ItemSummary,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateItemResponse {
+    pub create_item: CreatedItem,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DuplicateItemResponse {
+    pub duplicate_item: CreatedItem,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeleteItemResponse {
+    pub delete_item: CreatedItem,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ChangeSimpleColumnValueResponse {
+    pub change_simple_column_value: CreatedItem,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ChangeMultipleColumnValuesResponse {
+    pub change_multiple_column_values: CreatedItem,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreatedItem {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateGroupResponse {
+    pub create_group: GroupReference,
+}
+
+/// Names resolved from the API so IDs in column values can be displayed readably.
+#[derive(Debug, Default)]
+pub struct Lookups {
+    pub people: HashMap<i64, String>,
+    // Status column ID -> label index -> label
+    pub status_labels: HashMap<String, HashMap<i64, String>>,
+    // Column ID -> transform from the config, applied before anything else
+    pub transforms: BTreeMap<String, ColumnTransform>,
+}
+
+/// An item with its column values decoded to display strings, used for JSON output.
 #[derive(Debug, Serialize)]
-pub struct CreateItemVariables {
-    pub board_id: String,
+pub struct DecodedItem {
+    pub id: String,
+    pub name: String,
     pub group_id: String,
-    pub item_name: String,
-    pub column_values: String,
+    pub group: String,
+    pub column_values: BTreeMap<String, String>,
+}
+
+/// Hours and item count logged for one client, used by the `summary` subcommand.
+#[derive(Debug, Serialize)]
+pub struct ClientSummary {
+    pub client: String,
+    pub hours: f64,
+    pub items: usize,
+}
+
+/// Hours logged for one activity and their share of all the hours, used by `stats`.
+#[derive(Debug, Serialize)]
+pub struct ActivitySummary {
+    pub activity: String,
+    pub hours: f64,
+    // Of the hours counted, rounded to one decimal
+    pub percent: f64,
+}
+
+/// One row of a `bulk-add` CSV file, matched to the header row by column name.
+#[derive(Debug, Deserialize)]
+pub struct BulkAddRow {
+    pub year: String,
+    pub name: String,
+    pub activity: String,
+    pub date: String,
+    pub client: String,
+    pub wi: String,
+    pub hours: String,
+}
+
+/// Turn a raw column value as returned by the API into a human-readable string.
+///
+/// Text, date, linked item, people and status values are reduced to their text, date, IDs or
+/// label index; anything else, including malformed JSON, is shown as-is.
+pub fn decode_column_value(raw: &str) -> String {
+    // Parse the JSON value if it's a JSON string, otherwise use as-is
+    if !(raw.starts_with('{') || raw.starts_with('[')) {
+        // Remove quotes from string values
+        return raw.trim_matches('"').to_string();
+    }
+
+    let Ok(parsed) = serde_json::from_str::<serde_json::Value>(raw) else {
+        return raw.to_string();
+    };
+
+    if let Some(text) = parsed.get("text").and_then(|v| v.as_str()) {
+        text.to_string()
+    } else if let Some(date) = parsed.get("date").and_then(|v| v.as_str()) {
+        date.to_string()
+    } else if let Some(ids) = parsed.get("ids").and_then(|v| v.as_array()) {
+        let id_strings: Vec<String> = ids
+            .iter()
+            .filter_map(|v| v.as_i64().map(|id| id.to_string()))
+            .collect();
+        id_strings.join(", ")
+    } else if let Some(persons) = parsed.get("personsAndTeams").and_then(|v| v.as_array()) {
+        let person_ids: Vec<String> = persons
+            .iter()
+            .filter_map(|p| {
+                p.get("id")
+                    .and_then(|v| v.as_i64())
+                    .map(|id| id.to_string())
+            })
+            .collect();
+        person_ids.join(", ")
+    } else if let Some(index) = parsed.get("index").and_then(|v| v.as_i64()) {
+        index.to_string()
+    } else {
+        // Fallback: just display the raw value
+        raw.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_column_value, GraphQLError};
+    use serde_json::json;
+
+    #[test]
+    fn plain_values_lose_their_quotes() {
+        assert_eq!(decode_column_value("\"7.5\""), "7.5");
+        assert_eq!(decode_column_value("\"Acme\""), "Acme");
+        assert_eq!(decode_column_value("8"), "8");
+        assert_eq!(decode_column_value(""), "");
+    }
+
+    #[test]
+    fn text_values() {
+        assert_eq!(decode_column_value(r#"{"text":"Acme"}"#), "Acme");
+    }
+
+    #[test]
+    fn date_values() {
+        assert_eq!(
+            decode_column_value(r#"{"date":"2025-09-05","changed_at":"2025-09-05T10:00:00Z"}"#),
+            "2025-09-05"
+        );
+    }
+
+    #[test]
+    fn linked_item_ids() {
+        assert_eq!(decode_column_value(r#"{"ids":[11,22]}"#), "11, 22");
+        assert_eq!(decode_column_value(r#"{"ids":[]}"#), "");
+    }
+
+    #[test]
+    fn people_ids() {
+        assert_eq!(
+            decode_column_value(
+                r#"{"personsAndTeams":[{"id":42,"kind":"person"},{"id":7,"kind":"team"}]}"#
+            ),
+            "42, 7"
+        );
+        assert_eq!(decode_column_value(r#"{"personsAndTeams":[]}"#), "");
+    }
+
+    #[test]
+    fn status_index() {
+        assert_eq!(decode_column_value(r#"{"index":1,"post_id":null}"#), "1");
+    }
+
+    #[test]
+    fn unknown_shapes_are_shown_raw() {
+        assert_eq!(decode_column_value(r#"{"url":"x"}"#), r#"{"url":"x"}"#);
+        assert_eq!(decode_column_value("[1,2]"), "[1,2]");
+    }
+
+    #[test]
+    fn malformed_json_is_shown_raw() {
+        assert_eq!(decode_column_value(r#"{"text":"#), r#"{"text":"#);
+    }
+
+    #[test]
+    fn column_mismatches_name_the_column_and_the_expected_type() {
+        let error = |error_data: serde_json::Value| -> GraphQLError {
+            serde_json::from_value(json!({
+                "message": "invalid value",
+                "extensions": { "code": "ColumnValueException", "error_data": error_data }
+            }))
+            .unwrap()
+        };
+        let sent = json!({ "text__1": "Acme", "numbers__1": "abc" });
+
+        // The column is found by the value sent when error_data doesn't name it
+        assert_eq!(
+            error(json!({ "column_value": "abc", "column_type": "NumericColumn" }))
+                .column_mismatch(&sent)
+                .as_deref(),
+            Some("column numbers__1 expects a number, got 'abc'")
+        );
+        assert_eq!(
+            error(json!({ "column_id": "date4", "column_type": "date" }))
+                .column_mismatch(&sent)
+                .as_deref(),
+            Some("column date4 expects a date")
+        );
+        assert_eq!(error(json!({ "item_id": 5 })).column_mismatch(&sent), None);
+    }
 }
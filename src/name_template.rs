@@ -0,0 +1,87 @@
+use anyhow::{bail, Result};
+
+/// Fields of the `add` subcommand that a name template may refer to, e.g. `{date} - {client}`.
+pub const PLACEHOLDERS: [&str; 6] = ["year", "activity", "date", "client", "wi", "hours"];
+
+enum Segment<'a> {
+    Literal(&'a str),
+    Field(&'a str),
+}
+
+fn parse(template: &str) -> Result<Vec<Segment<'_>>> {
+    let mut segments = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        if start > 0 {
+            segments.push(Segment::Literal(&rest[..start]));
+        }
+        let Some(end) = rest[start..].find('}') else {
+            bail!("Invalid name template `{}`: unclosed `{{`", template);
+        };
+        let field = &rest[start + 1..start + end];
+        if !PLACEHOLDERS.contains(&field) {
+            bail!(
+                "Invalid name template `{}`: unknown placeholder {{{}}}, valid placeholders are: {}",
+                template,
+                field,
+                PLACEHOLDERS.map(|name| format!("{{{}}}", name)).join(", ")
+            );
+        }
+        segments.push(Segment::Field(field));
+        rest = &rest[start + end + 1..];
+    }
+    if !rest.is_empty() {
+        segments.push(Segment::Literal(rest));
+    }
+    Ok(segments)
+}
+
+/// Check that every placeholder in the template is known, before anything is rendered.
+pub fn validate(template: &str) -> Result<()> {
+    parse(template).map(|_| ())
+}
+
+/// Substitute the placeholders with the matching values, a missing value renders as empty.
+pub fn render(template: &str, values: &[(&str, &str)]) -> Result<String> {
+    let mut name = String::new();
+    for segment in parse(template)? {
+        match segment {
+            Segment::Literal(text) => name.push_str(text),
+            Segment::Field(field) => {
+                if let Some((_, value)) = values.iter().find(|(key, _)| *key == field) {
+                    name.push_str(value);
+                }
+            }
+        }
+    }
+    Ok(name.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_placeholders() {
+        let values = [("date", "2025-09-05"), ("client", "Acme"), ("wi", "WI-7")];
+        assert_eq!(
+            render("{date} - {client} - {wi}", &values).unwrap(),
+            "2025-09-05 - Acme - WI-7"
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_placeholders() {
+        let error = validate("{date} {customer}").unwrap_err().to_string();
+        assert!(
+            error.contains("unknown placeholder {customer}"),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn rejects_unclosed_braces() {
+        assert!(validate("{date").is_err());
+    }
+}
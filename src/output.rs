@@ -0,0 +1,71 @@
+use owo_colors::{OwoColorize, Stream};
+use std::fmt::Display;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static STATUS_TO_STDERR: AtomicBool = AtomicBool::new(false);
+
+/// Turn colors off regardless of the terminal, for `--no-color`.
+///
+/// Otherwise colors are used only when the stream is a terminal and `NO_COLOR` is not set.
+pub fn disable_colors() {
+    owo_colors::set_override(false);
+}
+
+/// Send the success, warning and failure lines to stderr from now on, keeping stdout for
+/// machine-readable output.
+pub fn status_to_stderr() {
+    STATUS_TO_STDERR.store(true, Ordering::Relaxed);
+}
+
+/// Send the status lines back to stdout, undoing `status_to_stderr` for the next command.
+pub fn status_to_stdout() {
+    STATUS_TO_STDERR.store(false, Ordering::Relaxed);
+}
+
+fn status(line: String, color: fn(&String) -> String) {
+    crate::progress::suspend(|| {
+        if STATUS_TO_STDERR.load(Ordering::Relaxed) {
+            eprintln!("{}", line.if_supports_color(Stream::Stderr, color));
+        } else {
+            println!("{}", line.if_supports_color(Stream::Stdout, color));
+        }
+    });
+}
+
+/// Print a line saying something worked, in green.
+pub fn success(message: impl Display) {
+    let line = format!("✅ {}", message);
+    status(line, |line| line.green().to_string());
+}
+
+/// Print a neutral line of information, e.g. a value that was filled in.
+pub fn info(message: impl Display) {
+    let line = format!("ℹ️  {}", message);
+    status(line, |line| line.to_string());
+}
+
+/// Print a line about something that worked but deserves attention, in yellow.
+pub fn warning(message: impl Display) {
+    let line = format!("⚠️  {}", message);
+    status(line, |line| line.yellow().to_string());
+}
+
+/// Print a line saying a check or step failed, in red, as part of the regular output.
+pub fn failure(message: impl Display) {
+    let line = format!("❌ {}", message);
+    status(line, |line| line.red().to_string());
+}
+
+/// Print the error that ended the command to stderr, in red.
+pub fn error(message: impl Display) {
+    let line = format!("❌ {}", message);
+    eprintln!(
+        "{}",
+        line.if_supports_color(Stream::Stderr, |line| line.red())
+    );
+}
+
+/// Print a suggestion following an error to stderr.
+pub fn hint(message: impl Display) {
+    eprintln!("💡 {}", message);
+}
@@ -0,0 +1,71 @@
+use indicatif::{ProgressBar, ProgressStyle};
+use std::io::{self, IsTerminal};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+static QUIET: AtomicBool = AtomicBool::new(false);
+// The bar being drawn, so other output can be printed without tearing it
+static ACTIVE: Mutex<Option<ProgressBar>> = Mutex::new(None);
+
+/// Never show progress bars, for `--quiet`.
+pub fn disable() {
+    QUIET.store(true, Ordering::Relaxed);
+}
+
+/// A progress bar on stderr that is cleared again when dropped.
+///
+/// Hidden under `--quiet` and when stdout isn't a terminal, so scripted output stays clean.
+pub struct Progress(ProgressBar);
+
+impl Progress {
+    /// A bar counting up to `len` steps, e.g. one per item to create.
+    pub fn bar(len: u64, message: &str) -> Self {
+        Self::start(
+            ProgressBar::new(len),
+            "{spinner} {msg} [{bar:30}] {pos}/{len}",
+            message,
+        )
+    }
+
+    /// A spinner for work of unknown length, e.g. one tick per page fetched.
+    pub fn spinner(message: &str) -> Self {
+        Self::start(
+            ProgressBar::new_spinner(),
+            "{spinner} {msg} ({pos})",
+            message,
+        )
+    }
+
+    fn start(bar: ProgressBar, template: &str, message: &str) -> Self {
+        if QUIET.load(Ordering::Relaxed) || !io::stdout().is_terminal() {
+            return Progress(ProgressBar::hidden());
+        }
+        let style = ProgressStyle::with_template(template)
+            .expect("valid progress template")
+            .progress_chars("=> ");
+        let bar = bar.with_style(style).with_message(message.to_string());
+        *ACTIVE.lock().unwrap() = Some(bar.clone());
+        Progress(bar)
+    }
+
+    pub fn inc(&self, steps: u64) {
+        self.0.inc(steps);
+    }
+}
+
+impl Drop for Progress {
+    fn drop(&mut self) {
+        if !self.0.is_hidden() {
+            self.0.finish_and_clear();
+            ACTIVE.lock().unwrap().take();
+        }
+    }
+}
+
+/// Run `print` with the active progress bar, if any, taken off the screen meanwhile.
+pub fn suspend<R>(print: impl FnOnce() -> R) -> R {
+    match ACTIVE.lock().unwrap().as_ref() {
+        Some(bar) => bar.suspend(print),
+        None => print(),
+    }
+}
@@ -0,0 +1,192 @@
+use crate::config::Config;
+use crate::error::CliError;
+use crate::name_template;
+use anyhow::Result;
+use chrono::Datelike;
+use dialoguer::{Confirm, Input, Password, Select};
+use log::info;
+use std::io::{self, BufRead, IsTerminal};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static ASSUME_YES: AtomicBool = AtomicBool::new(false);
+
+/// Answer yes to every confirmation from now on, for `--yes`.
+pub fn assume_yes() {
+    ASSUME_YES.store(true, Ordering::Relaxed);
+}
+
+/// Ask a yes/no question before doing something that can't be undone, defaulting to no.
+///
+/// With `--yes` the answer is yes without asking. When there is no terminal to ask on, it's an
+/// error naming `flag`, the command's own option for saying yes up front, so a script never
+/// gets a yes it didn't ask for.
+pub fn confirm(question: &str, flag: &str) -> Result<bool> {
+    if ASSUME_YES.load(Ordering::Relaxed) {
+        return Ok(true);
+    }
+    if !io::stdin().is_terminal() || !io::stderr().is_terminal() {
+        return Err(CliError::InvalidInput(format!(
+            "{} Not running in a terminal to ask, pass {} or --yes to go ahead",
+            question, flag
+        ))
+        .into());
+    }
+    Ok(Confirm::new()
+        .with_prompt(question)
+        .default(false)
+        .interact()?)
+}
+
+/// Ask for a value without echoing it, or read the first line of stdin when it isn't a terminal.
+pub fn secret(prompt: &str) -> Result<String> {
+    if !io::stdin().is_terminal() {
+        let mut line = String::new();
+        io::stdin().lock().read_line(&mut line)?;
+        return Ok(line.trim().to_string());
+    }
+    Ok(Password::new()
+        .with_prompt(prompt)
+        .interact()?
+        .trim()
+        .to_string())
+}
+
+/// The fields of the `add` subcommand, `None` where the value has yet to be asked for.
+#[derive(Clone, Default)]
+pub struct AddFields {
+    pub year: Option<String>,
+    pub name: Option<String>,
+    pub activity: Option<String>,
+    pub date: Option<String>,
+    pub client: Option<String>,
+    pub wi: Option<String>,
+    pub hours: Option<String>,
+}
+
+impl AddFields {
+    /// Fill in a name template from the other fields of an item.
+    pub fn render_name(&self, template: &str) -> Result<String> {
+        fn field(value: &Option<String>) -> &str {
+            value.as_deref().unwrap_or_default()
+        }
+        let name = name_template::render(
+            template,
+            &[
+                ("year", field(&self.year)),
+                ("activity", field(&self.activity)),
+                ("date", field(&self.date)),
+                ("client", field(&self.client)),
+                ("wi", field(&self.wi)),
+                ("hours", field(&self.hours)),
+            ],
+        )?;
+        if name.is_empty() {
+            return Err(CliError::InvalidInput(format!(
+                "The name template `{}` produced an empty name",
+                template
+            ))
+            .into());
+        }
+        info!("Generated item name: {}", name);
+        Ok(name)
+    }
+}
+
+/// Ask on the terminal for every field that wasn't given on the command line.
+///
+/// Each answer is validated as it's entered, so a typo is asked for again instead of failing
+/// the whole command. With a name template the name is asked for last, offering the rendered
+/// template as the default.
+pub fn fill_add_fields(
+    config: &Config,
+    fields: &mut AddFields,
+    name_template: Option<&str>,
+) -> Result<()> {
+    let today = config.today();
+
+    if fields.date.is_none() {
+        fields.date = Some(text(
+            "Date (YYYY-MM-DD, MM/DD/YYYY or DD.MM.YYYY)",
+            Some(today.to_string()),
+            |date| {
+                crate::parse_date(date)
+                    .map(|_| ())
+                    .map_err(|e| e.to_string())
+            },
+        )?);
+    }
+    if fields.year.is_none() {
+        // The group usually matches the year of the date just entered
+        let year = fields
+            .date
+            .as_deref()
+            .and_then(|date| crate::parse_date(date).ok())
+            .map_or(today.year(), |date| date.year())
+            .to_string();
+        fields.year = Some(text("Year group", Some(year), not_empty)?);
+    }
+    if fields.name.is_none() && name_template.is_none() {
+        fields.name = Some(text("Item name", None, not_empty)?);
+    }
+    if fields.activity.is_none() {
+        fields.activity = Some(select_activity(config)?);
+    }
+    if fields.client.is_none() {
+        fields.client = Some(text("Client", None, not_empty)?);
+    }
+    if fields.wi.is_none() {
+        fields.wi = Some(text("Work item", None, not_empty)?);
+    }
+    if fields.hours.is_none() {
+        let default = config.default_hours().to_string();
+        fields.hours = Some(text("Hours", Some(default), |hours| {
+            crate::parse_hours(hours)
+                .map(|_| ())
+                .map_err(|e| e.to_string())
+        })?);
+    }
+    if let (None, Some(template)) = (&fields.name, name_template) {
+        let default = fields.render_name(template).ok();
+        fields.name = Some(text("Item name", default, not_empty)?);
+    }
+
+    Ok(())
+}
+
+fn text(
+    prompt: &str,
+    default: Option<String>,
+    validate: impl Fn(&str) -> Result<(), String>,
+) -> Result<String> {
+    let mut input = Input::<String>::new().with_prompt(prompt);
+    if let Some(default) = default {
+        input = input.default(default);
+    }
+    let answer = input
+        .validate_with(|answer: &String| validate(answer.trim()))
+        .interact_text()?;
+    Ok(answer.trim().to_string())
+}
+
+fn not_empty(value: &str) -> Result<(), String> {
+    if value.is_empty() {
+        Err("A value is required".to_string())
+    } else {
+        Ok(())
+    }
+}
+
+/// Let the user pick one of the known activity keywords.
+fn select_activity(config: &Config) -> Result<String> {
+    let keywords = config.activity_keywords();
+    let default = keywords
+        .iter()
+        .position(|keyword| keyword == "billable")
+        .unwrap_or(0);
+    let selection = Select::new()
+        .with_prompt("Activity")
+        .items(&keywords)
+        .default(default)
+        .interact()?;
+    Ok(keywords[selection].clone())
+}
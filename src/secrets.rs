@@ -0,0 +1,46 @@
+use anyhow::{anyhow, Result};
+use keyring::{Entry, Error};
+
+// Where `login` stores the API key in the platform keychain
+const SERVICE: &str = "monday-claim";
+const USERNAME: &str = "api_key";
+
+/// The `api_key` value that means "read the key from the keychain".
+pub const KEYRING_API_KEY: &str = "keyring";
+
+fn entry() -> Result<Entry> {
+    Entry::new(SERVICE, USERNAME).map_err(|e| anyhow!("The keychain isn't available: {}", e))
+}
+
+/// The API key stored by `login`.
+pub fn load_api_key() -> Result<String> {
+    match entry()?.get_password() {
+        Ok(api_key) => Ok(api_key),
+        Err(Error::NoEntry) => Err(anyhow!(
+            "No API key in the keychain, store one with `monday-claim login`"
+        )),
+        Err(e) => Err(anyhow!(
+            "Failed to read the API key from the keychain: {}",
+            e
+        )),
+    }
+}
+
+/// Store the API key in the keychain, replacing any stored before.
+pub fn store_api_key(api_key: &str) -> Result<()> {
+    entry()?
+        .set_password(api_key)
+        .map_err(|e| anyhow!("Failed to store the API key in the keychain: {}", e))
+}
+
+/// Remove the stored API key, returning whether there was one.
+pub fn delete_api_key() -> Result<bool> {
+    match entry()?.delete_credential() {
+        Ok(()) => Ok(true),
+        Err(Error::NoEntry) => Ok(false),
+        Err(e) => Err(anyhow!(
+            "Failed to remove the API key from the keychain: {}",
+            e
+        )),
+    }
+}
@@ -0,0 +1,85 @@
+use anyhow::{anyhow, Result};
+use directories::ProjectDirs;
+use log::debug;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// The values of the last `add` on a board, offered again by `add --use-last`.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct LastUsed {
+    pub client: Option<String>,
+    pub wi: Option<String>,
+    pub activity: Option<String>,
+}
+
+/// Everything remembered between runs, stored as JSON in the config directory.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct State {
+    // Keyed by board ID so entries for one board are never offered on another
+    #[serde(default)]
+    boards: BTreeMap<u64, LastUsed>,
+    // Board ID -> highest item ID listed by the last `query --since-last`
+    #[serde(default)]
+    query_markers: BTreeMap<u64, u64>,
+}
+
+fn state_path() -> Option<PathBuf> {
+    ProjectDirs::from("", "", "monday-claim").map(|dirs| dirs.config_dir().join("state.json"))
+}
+
+/// Where `repl` keeps the lines entered, next to the state file.
+pub fn history_path() -> Option<PathBuf> {
+    ProjectDirs::from("", "", "monday-claim").map(|dirs| dirs.config_dir().join("repl_history.txt"))
+}
+
+fn load() -> State {
+    let Some(path) = state_path() else {
+        return State::default();
+    };
+    let Ok(content) = fs::read_to_string(&path) else {
+        return State::default();
+    };
+    serde_json::from_str(&content).unwrap_or_else(|e| {
+        debug!("Ignoring unreadable state {}: {}", path.display(), e);
+        State::default()
+    })
+}
+
+/// The values last used on a board, all `None` if nothing was added there yet.
+pub fn last_used(board_id: u64) -> LastUsed {
+    load().boards.remove(&board_id).unwrap_or_default()
+}
+
+pub fn store_last_used(board_id: u64, last_used: LastUsed) -> Result<()> {
+    let mut state = load();
+    state.boards.insert(board_id, last_used);
+    save(&state)
+}
+
+/// The highest item ID the last `query --since-last` listed on a board, items created since
+/// have higher IDs.
+pub fn query_marker(board_id: u64) -> Option<u64> {
+    load().query_markers.get(&board_id).copied()
+}
+
+/// Remember the highest item ID listed on a board, or forget it with `None`.
+pub fn store_query_marker(board_id: u64, marker: Option<u64>) -> Result<()> {
+    let mut state = load();
+    match marker {
+        Some(marker) => state.query_markers.insert(board_id, marker),
+        None => state.query_markers.remove(&board_id),
+    };
+    save(&state)
+}
+
+fn save(state: &State) -> Result<()> {
+    let path = state_path().ok_or_else(|| anyhow!("No config directory available"))?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(&path, serde_json::to_string_pretty(state)?)?;
+    debug!("Saved state in {}", path.display());
+    Ok(())
+}
@@ -0,0 +1,156 @@
+use crate::config::Config;
+use crate::models::{Board, Item, Lookups};
+use anyhow::{anyhow, Result};
+use chrono::NaiveDate;
+use rust_xlsxwriter::{cell_range, Format, Formula, Workbook, Worksheet};
+
+// Excel rejects longer sheet names and these characters in them
+const MAX_SHEET_NAME_LENGTH: usize = 31;
+const INVALID_SHEET_NAME_CHARS: [char; 7] = ['[', ']', ':', '*', '?', '/', '\\'];
+
+/// Write the board's items to an XLSX workbook with a sheet per group that has items, in the
+/// board's group order. Returns the number of sheets written.
+///
+/// Dates and hours are written as typed cells so they can be sorted and summed, every other
+/// column as the text `query` shows. Each sheet ends with a row totalling the hours.
+pub fn write_items(path: &str, config: &Config, board: &Board, lookups: &Lookups) -> Result<usize> {
+    let mut workbook = Workbook::new();
+    let mut sheet_names: Vec<String> = Vec::new();
+
+    for group in &board.groups {
+        let items: Vec<&Item> = board
+            .items_page
+            .items
+            .iter()
+            .filter(|item| item.group.id == group.id)
+            .collect();
+        if items.is_empty() {
+            continue;
+        }
+
+        let name = sheet_name(&group.title, &sheet_names);
+        let worksheet = workbook.add_worksheet();
+        worksheet.set_name(&name)?;
+        write_sheet(worksheet, config, board, lookups, &items)?;
+        sheet_names.push(name);
+    }
+
+    if sheet_names.is_empty() {
+        return Err(anyhow!("No items to export"));
+    }
+    workbook
+        .save(path)
+        .map_err(|e| anyhow!("Failed to write {}: {}", path, e))?;
+    Ok(sheet_names.len())
+}
+
+fn write_sheet(
+    worksheet: &mut Worksheet,
+    config: &Config,
+    board: &Board,
+    lookups: &Lookups,
+    items: &[&Item],
+) -> Result<()> {
+    let bold = Format::new().set_bold();
+    let date_format = Format::new().set_num_format("yyyy-mm-dd");
+
+    let mut column_ids: Vec<&str> = Vec::new();
+    for column in items.iter().flat_map(|item| &item.column_values) {
+        if !column_ids.contains(&column.id.as_str()) {
+            column_ids.push(&column.id);
+        }
+    }
+
+    worksheet.write_with_format(0, 0, "ID", &bold)?;
+    worksheet.write_with_format(0, 1, "Name", &bold)?;
+    for (position, column_id) in column_ids.iter().enumerate() {
+        // The board's title where it has one, the ID is all a subitem column has
+        let title = board
+            .columns
+            .iter()
+            .find(|column| column.id == *column_id && !column.title.is_empty())
+            .map_or(*column_id, |column| column.title.as_str());
+        worksheet.write_with_format(0, position as u16 + 2, title, &bold)?;
+    }
+    worksheet.set_freeze_panes(1, 0)?;
+
+    for (index, item) in items.iter().enumerate() {
+        let row = index as u32 + 1;
+        worksheet.write(row, 0, &item.id)?;
+        worksheet.write(row, 1, &item.name)?;
+        for (position, column_id) in column_ids.iter().enumerate() {
+            let col = position as u16 + 2;
+            let value = crate::decoded_column(item, column_id);
+            if *column_id == config.columns.date {
+                if let Ok(date) = NaiveDate::parse_from_str(&value, "%Y-%m-%d") {
+                    worksheet.write_with_format(row, col, &date, &date_format)?;
+                    continue;
+                }
+            } else if *column_id == config.columns.hours {
+                if let Ok(hours) = value.trim().parse::<f64>() {
+                    worksheet.write(row, col, hours)?;
+                    continue;
+                }
+            }
+            let shown = crate::display_column(item, column_id, lookups);
+            if !shown.is_empty() {
+                worksheet.write(row, col, shown)?;
+            }
+        }
+    }
+
+    let total_row = items.len() as u32 + 1;
+    worksheet.write_with_format(total_row, 1, "Total", &bold)?;
+    if let Some(position) = column_ids
+        .iter()
+        .position(|column_id| *column_id == config.columns.hours)
+    {
+        let col = position as u16 + 2;
+        // Shown by viewers that don't calculate formulas themselves
+        let total: f64 = items
+            .iter()
+            .filter_map(|item| {
+                crate::decoded_column(item, &config.columns.hours)
+                    .trim()
+                    .parse::<f64>()
+                    .ok()
+            })
+            .sum();
+        // A formula, so the total follows any hours edited in the spreadsheet
+        let formula = Formula::new(format!("=SUM({})", cell_range(1, col, total_row - 1, col)))
+            .set_result(total.to_string());
+        worksheet.write_formula_with_format(total_row, col, formula, &bold)?;
+    }
+
+    worksheet.autofit();
+    Ok(())
+}
+
+/// A valid sheet name for a group title that no earlier sheet has taken.
+fn sheet_name(title: &str, taken: &[String]) -> String {
+    let cleaned: String = title
+        .chars()
+        .map(|c| {
+            if INVALID_SHEET_NAME_CHARS.contains(&c) {
+                '-'
+            } else {
+                c
+            }
+        })
+        .collect();
+    let cleaned = match cleaned.trim().trim_matches('\'') {
+        "" => "Group".to_string(),
+        cleaned => cleaned.to_string(),
+    };
+
+    let mut name: String = cleaned.chars().take(MAX_SHEET_NAME_LENGTH).collect();
+    let mut copy = 1;
+    // Excel compares sheet names ignoring case
+    while taken.iter().any(|other| other.eq_ignore_ascii_case(&name)) {
+        copy += 1;
+        let suffix = format!(" ({})", copy);
+        let kept = MAX_SHEET_NAME_LENGTH - suffix.chars().count();
+        name = cleaned.chars().take(kept).collect::<String>() + &suffix;
+    }
+    name
+}
@@ -0,0 +1,2132 @@
+//! End-to-end tests that run the binary against a mock Monday.com API.
+
+use serde_json::{json, Value};
+use std::fs;
+use std::process::{Output, Stdio};
+use tempfile::TempDir;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use wiremock::matchers::{body_string_contains, method};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// A mock API server plus a scratch directory holding the config file and the cache.
+struct TestEnv {
+    server: MockServer,
+    dir: TempDir,
+}
+
+impl TestEnv {
+    async fn new() -> Self {
+        let server = MockServer::start().await;
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("config.toml"),
+            format!(
+                "api_key = \"test-key\"\nboard_id = \"100\"\nuser_id = \"42\"\napi_url = \"{}\"\nmax_retries = 0\n",
+                server.uri()
+            ),
+        )
+        .unwrap();
+        TestEnv { server, dir }
+    }
+
+    /// Add settings to the end of the config file.
+    fn append_config(&self, settings: &str) {
+        let path = self.dir.path().join("config.toml");
+        let config = fs::read_to_string(&path).unwrap();
+        fs::write(path, config + settings).unwrap();
+    }
+
+    /// Answer every request whose body contains `operation` (e.g. the query name) with `data`.
+    async fn respond(&self, operation: &str, data: Value) {
+        Mock::given(method("POST"))
+            .and(body_string_contains(operation))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "data": data })))
+            .mount(&self.server)
+            .await;
+    }
+
+    async fn respond_with_errors(&self, operation: &str, errors: Value) {
+        Mock::given(method("POST"))
+            .and(body_string_contains(operation))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "errors": errors })))
+            .mount(&self.server)
+            .await;
+    }
+
+    async fn run(&self, args: &[&str]) -> Output {
+        self.command(args).output().await.unwrap()
+    }
+
+    /// Like `run`, with `input` on stdin.
+    async fn run_with_input(&self, args: &[&str], input: &str) -> Output {
+        let mut child = self
+            .command(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let mut stdin = child.stdin.take().unwrap();
+        stdin.write_all(input.as_bytes()).await.unwrap();
+        drop(stdin);
+        child.wait_with_output().await.unwrap()
+    }
+
+    fn command(&self, args: &[&str]) -> Command {
+        let mut command = Command::new(env!("CARGO_BIN_EXE_monday-claim"));
+        command
+            .arg("--config")
+            .arg(self.dir.path().join("config.toml"))
+            .args(args)
+            // Keep the cache and state of each test to itself and the developer's environment out
+            .env("XDG_CACHE_HOME", self.dir.path().join("cache"))
+            .env("XDG_CONFIG_HOME", self.dir.path().join("config"))
+            .env_remove("MONDAY_API_KEY")
+            .env_remove("MONDAY_BOARD_ID")
+            .env_remove("MONDAY_USER_ID")
+            .env_remove("RUST_LOG");
+        command
+    }
+
+    /// Bodies of every request received so far whose query contains `operation`.
+    async fn requests_for(&self, operation: &str) -> Vec<Value> {
+        self.server
+            .received_requests()
+            .await
+            .unwrap()
+            .iter()
+            .map(|request| serde_json::from_slice::<Value>(&request.body).unwrap())
+            .filter(|body| body["query"].as_str().unwrap().contains(operation))
+            .collect()
+    }
+}
+
+fn groups() -> Value {
+    json!({ "boards": [{ "groups": [
+        { "id": "g2024", "title": "2024" },
+        { "id": "g2025", "title": "2025" }
+    ] }] })
+}
+
+/// A GetBoardStructure response for board 100 with one group, 2025, and the given items.
+fn board(items: Value) -> Value {
+    board_with_columns(json!([]), items)
+}
+
+fn board_with_columns(columns: Value, items: Value) -> Value {
+    json!({ "boards": [{
+        "name": "Time",
+        "id": "100",
+        "groups": [{ "id": "g2025", "title": "2025" }],
+        "columns": columns,
+        "items_page": { "cursor": null, "items": items }
+    }] })
+}
+
+/// A GetItemsOnDate response without any items, as for a day nothing was logged on yet.
+fn no_items_on_date() -> Value {
+    json!({ "boards": [{ "items_page": { "cursor": null, "items": [] } }] })
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+const ADD_ARGS: [&str; 15] = [
+    "add",
+    "--name",
+    "Jane Doe",
+    "--activity",
+    "billable",
+    "--date",
+    "2025-09-05",
+    "--client",
+    "Acme",
+    "--wi",
+    "WI-7",
+    "--hours",
+    "7.5",
+    "--year",
+    "2025",
+];
+
+#[tokio::test]
+async fn add_sends_the_expected_column_values() {
+    let env = TestEnv::new().await;
+    env.respond("GetBoardGroups", groups()).await;
+    env.respond(
+        "CreateItem",
+        json!({ "create_item": { "id": "555", "name": "Jane Doe" } }),
+    )
+    .await;
+    env.respond("GetItemsOnDate", no_items_on_date()).await;
+
+    let output = env.run(&ADD_ARGS).await;
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert!(stdout(&output).contains("Item created successfully! ID: 555"));
+
+    let requests = env.requests_for("CreateItem").await;
+    assert_eq!(requests.len(), 1);
+    let variables = &requests[0]["variables"];
+    assert_eq!(variables["boardId"], json!(100));
+    assert_eq!(variables["groupId"], json!("g2025"));
+    assert_eq!(variables["itemName"], json!("Jane Doe"));
+
+    // Monday expects the column values as a JSON-encoded string
+    let column_values: Value =
+        serde_json::from_str(variables["columnValues"].as_str().unwrap()).unwrap();
+    assert_eq!(
+        column_values,
+        json!({
+            "person": { "personsAndTeams": [{ "id": 42, "kind": "person" }] },
+            "status": { "index": 1 },
+            "date4": { "date": "2025-09-05" },
+            "text__1": "Acme",
+            "text8__1": "WI-7",
+            "numbers__1": "7.5"
+        })
+    );
+}
+
+#[tokio::test]
+async fn log_week_adds_an_item_per_weekday() {
+    let env = TestEnv::new().await;
+    env.respond("GetBoardGroups", groups()).await;
+    env.respond(
+        "CreateItem",
+        json!({ "create_item": { "id": "555", "name": "Jane Doe" } }),
+    )
+    .await;
+    env.respond("GetItemsOnDate", no_items_on_date()).await;
+    let args = [
+        "log-week",
+        "--start",
+        "2025-09-01",
+        "--name",
+        "Jane Doe",
+        "--activity",
+        "billable",
+        "--client",
+        "Acme",
+        "--wi",
+        "WI-7",
+        "--hours",
+        "8",
+    ];
+    let created_dates = |requests: &[Value]| -> Vec<String> {
+        requests
+            .iter()
+            .map(|request| {
+                assert_eq!(request["variables"]["groupId"], json!("g2025"));
+                let column_values: Value =
+                    serde_json::from_str(request["variables"]["columnValues"].as_str().unwrap())
+                        .unwrap();
+                column_values["date4"]["date"].as_str().unwrap().to_string()
+            })
+            .collect()
+    };
+
+    let output = env.run(&args).await;
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert!(stdout(&output).contains("Created 5 of 5 items"));
+    assert_eq!(
+        created_dates(&env.requests_for("CreateItem").await),
+        [
+            "2025-09-01",
+            "2025-09-02",
+            "2025-09-03",
+            "2025-09-04",
+            "2025-09-05"
+        ]
+    );
+
+    // The week has to start on a Monday
+    let mut tuesday = args.to_vec();
+    tuesday[2] = "2025-09-02";
+    let output = env.run(&tuesday).await;
+    assert_eq!(output.status.code(), Some(3));
+    assert!(stderr(&output).contains("--start 2025-09-02 is a Tuesday, expected a Monday"));
+    assert_eq!(env.requests_for("CreateItem").await.len(), 5);
+
+    env.server.reset().await;
+    env.respond("GetBoardGroups", groups()).await;
+    env.respond(
+        "CreateItem",
+        json!({ "create_item": { "id": "555", "name": "Jane Doe" } }),
+    )
+    .await;
+    env.respond("GetItemsOnDate", no_items_on_date()).await;
+    let mut whole_week = args.to_vec();
+    whole_week.push("--include-weekends");
+    let output = env.run(&whole_week).await;
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert!(stdout(&output).contains("Created 7 of 7 items"));
+    let dates = created_dates(&env.requests_for("CreateItem").await);
+    assert_eq!(dates.len(), 7);
+    assert_eq!(dates[5..], ["2025-09-06", "2025-09-07"]);
+}
+
+#[tokio::test]
+async fn use_last_reuses_the_previous_add_on_the_same_board() {
+    let env = TestEnv::new().await;
+    env.respond("GetBoardGroups", groups()).await;
+    env.respond(
+        "CreateItem",
+        json!({ "create_item": { "id": "555", "name": "Jane Doe" } }),
+    )
+    .await;
+    env.respond("GetItemsOnDate", no_items_on_date()).await;
+    let output = env.run(&ADD_ARGS).await;
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+
+    let args = [
+        "add",
+        "--use-last",
+        "--name",
+        "Jane Doe",
+        "--date",
+        "2025-09-08",
+        "--hours",
+        "8",
+        "--year",
+        "2025",
+    ];
+    let output = env.run(&args).await;
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    let requests = env.requests_for("CreateItem").await;
+    assert_eq!(requests.len(), 2);
+    let column_values: Value =
+        serde_json::from_str(requests[1]["variables"]["columnValues"].as_str().unwrap()).unwrap();
+    assert_eq!(column_values["text__1"], json!("Acme"));
+    assert_eq!(column_values["text8__1"], json!("WI-7"));
+    assert_eq!(column_values["status"], json!({ "index": 1 }));
+    assert_eq!(column_values["date4"], json!({ "date": "2025-09-08" }));
+
+    // Another board has no last add to reuse
+    let mut other_board = vec!["--board-id", "200"];
+    other_board.extend(args);
+    let output = env.run(&other_board).await;
+    assert_eq!(output.status.code(), Some(3));
+    assert!(stderr(&output).contains("No last-used value for --client on board 200 yet"));
+    assert_eq!(env.requests_for("CreateItem").await.len(), 2);
+}
+
+#[tokio::test]
+async fn add_verify_warns_when_the_person_column_lacks_the_user() {
+    let env = TestEnv::new().await;
+    env.respond("GetBoardGroups", groups()).await;
+    env.respond(
+        "CreateItem",
+        json!({ "create_item": { "id": "555", "name": "Jane Doe" } }),
+    )
+    .await;
+    env.respond("GetItemsOnDate", no_items_on_date()).await;
+    // As if user_id belonged to nobody on the account, so Monday.com stored someone else
+    env.respond(
+        "GetItem(",
+        json!({ "items": [{
+            "id": "555",
+            "name": "Jane Doe",
+            "group": { "id": "g2025" },
+            "column_values": [{
+                "id": "person",
+                "value": "{\"personsAndTeams\":[{\"id\":7,\"kind\":\"person\"}]}"
+            }],
+            "board": { "groups": [], "columns": [] }
+        }] }),
+    )
+    .await;
+
+    let mut args = ADD_ARGS.to_vec();
+    args.push("--verify");
+    let output = env.run(&args).await;
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert!(stdout(&output)
+        .contains("The person column (person) of item 555 doesn't include user_id 42 but 7"));
+
+    let requests = env.requests_for("GetItem(").await;
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0]["variables"]["columnIds"], json!(["person"]));
+
+    // Without --verify the item isn't read back
+    env.server.reset().await;
+    env.respond("GetBoardGroups", groups()).await;
+    env.respond(
+        "CreateItem",
+        json!({ "create_item": { "id": "556", "name": "Jane Doe" } }),
+    )
+    .await;
+    env.respond("GetItemsOnDate", no_items_on_date()).await;
+    let output = env.run(&ADD_ARGS).await;
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert!(env.requests_for("GetItem(").await.is_empty());
+}
+
+#[tokio::test]
+async fn audit_log_records_every_mutation() {
+    let env = TestEnv::new().await;
+    let path = env.dir.path().join("audit.jsonl");
+    env.append_config(&format!("audit_log = \"{}\"\n", path.display()));
+    env.respond("GetBoardGroups", groups()).await;
+    env.respond(
+        "CreateItem",
+        json!({ "create_item": { "id": "555", "name": "Jane Doe" } }),
+    )
+    .await;
+    env.respond("GetItemsOnDate", no_items_on_date()).await;
+    env.respond_with_errors(
+        "ChangeSimpleColumnValue",
+        json!([{ "message": "Item not found" }]),
+    )
+    .await;
+
+    let output = env.run(&ADD_ARGS).await;
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    // Dry runs change nothing, so they aren't recorded
+    let mut args = vec!["--dry-run"];
+    args.extend(ADD_ARGS);
+    let output = env.run(&args).await;
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    let output = env
+        .run(&["update-hours", "--id", "7", "--hours", "2"])
+        .await;
+    assert!(!output.status.success());
+    let output = env
+        .run(&["--audit-reads", "--refresh-cache", "groups"])
+        .await;
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+
+    let entries: Vec<Value> = fs::read_to_string(&path)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    // The reads of the add are left out unless --audit-reads is passed
+    assert_eq!(entries.len(), 3);
+    let created = &entries[0];
+    assert_eq!(created["command"], json!("add"));
+    assert_eq!(created["operation"], json!("CreateItem"));
+    assert_eq!(created["variables"]["itemName"], json!("Jane Doe"));
+    assert_eq!(created["id"], json!("555"));
+    assert_eq!(created["error"], Value::Null);
+    let failed = &entries[1];
+    assert_eq!(failed["command"], json!("update-hours"));
+    assert_eq!(failed["variables"]["itemId"], json!("7"));
+    assert_eq!(failed["id"], Value::Null);
+    assert!(failed["error"].as_str().unwrap().contains("Item not found"));
+    assert_eq!(entries[2]["operation"], json!("GetBoardGroups"));
+}
+
+#[tokio::test]
+async fn add_fails_when_the_year_group_is_missing() {
+    let env = TestEnv::new().await;
+    env.respond("GetBoardGroups", groups()).await;
+
+    let mut args = ADD_ARGS;
+    args[14] = "2030";
+    let output = env.run(&args).await;
+
+    assert_eq!(output.status.code(), Some(4));
+    let stderr = stderr(&output);
+    assert!(
+        stderr.contains("No group found with title: 2030"),
+        "{}",
+        stderr
+    );
+    assert!(stderr.contains("2025: g2025"), "{}", stderr);
+    assert!(env.requests_for("CreateItem").await.is_empty());
+}
+
+#[tokio::test]
+async fn add_reports_graphql_errors() {
+    let env = TestEnv::new().await;
+    env.respond("GetBoardGroups", groups()).await;
+    env.respond("GetItemsOnDate", no_items_on_date()).await;
+    env.respond_with_errors(
+        "CreateItem",
+        json!([{
+            "message": "Invalid value for the hours column",
+            "extensions": { "code": "ColumnValueException" }
+        }]),
+    )
+    .await;
+
+    let output = env.run(&ADD_ARGS).await;
+
+    assert_eq!(output.status.code(), Some(5));
+    assert!(
+        stderr(&output)
+            .contains("GraphQL Error: Invalid value for the hours column (ColumnValueException)"),
+        "{}",
+        stderr(&output)
+    );
+}
+
+#[tokio::test]
+async fn add_explains_column_value_errors_from_their_error_data() {
+    let env = TestEnv::new().await;
+    env.respond("GetBoardGroups", groups()).await;
+    env.respond("GetItemsOnDate", no_items_on_date()).await;
+    // As if the hours column were a date column instead
+    env.respond_with_errors(
+        "CreateItem",
+        json!([{
+            "message": "invalid value, please check our API documentation",
+            "extensions": {
+                "code": "ColumnValueException",
+                "error_data": { "column_value": "7.5", "column_type": "DateColumn" }
+            }
+        }]),
+    )
+    .await;
+
+    let output = env.run(&ADD_ARGS).await;
+    assert_eq!(output.status.code(), Some(5));
+    assert!(
+        stderr(&output)
+            .contains("column numbers__1 expects a date, got '7.5': GraphQL Error: invalid value"),
+        "{}",
+        stderr(&output)
+    );
+}
+
+#[tokio::test]
+async fn query_renders_the_items_table() {
+    let env = TestEnv::new().await;
+    env.respond(
+        "GetBoardStructure",
+        board_with_columns(
+            json!([{
+                "id": "status",
+                "type": "status",
+                "settings_str": "{\"labels\":{\"1\":\"Billable\"}}"
+            }]),
+            json!([{
+                "id": "555",
+                "name": "Jane Doe",
+                "group": { "id": "g2025" },
+                "column_values": [
+                    { "id": "status", "value": "{\"index\":1}" },
+                    { "id": "date4", "value": "{\"date\":\"2025-09-05\"}" },
+                    { "id": "numbers__1", "value": "\"7.5\"" }
+                ]
+            }]),
+        ),
+    )
+    .await;
+
+    let output = env.run(&["query"]).await;
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+
+    let expected = "\
+Found 1 groups:
++----------+-------------+
+| Group ID | Group Title |
++----------+-------------+
+| g2025    | 2025        |
++----------+-------------+
+
+Found 1 items:
++-----+----------+-------+---------------+--------------+-------------------+
+| ID  | Name     | Group | Column status | Column date4 | Column numbers__1 |
++-----+----------+-------+---------------+--------------+-------------------+
+| 555 | Jane Doe | 2025  | Billable      | 2025-09-05   | 7.5               |
++-----+----------+-------+---------------+--------------+-------------------+
+
+Total hours: 7.5
+  billable: 7.5
+";
+    assert_eq!(stdout(&output), expected);
+
+    // Values travel as variables rather than being spliced into the query text
+    let requests = env.requests_for("GetBoardStructure").await;
+    assert_eq!(
+        requests[0]["variables"],
+        json!({
+            "boardId": [100],
+            "limit": 10,
+            "withItems": true,
+            "withSubitems": false,
+            "queryParams": null,
+            "columnIds": null
+        })
+    );
+}
+
+#[tokio::test]
+async fn query_renders_items_with_unreadable_columns() {
+    let env = TestEnv::new().await;
+    env.respond(
+        "GetBoardStructure",
+        board(json!([{
+            "id": "555",
+            "name": "Jane Doe",
+            "group": { "id": "g2025" },
+            "column_values": [
+                { "value": "\"no ID\"" },
+                { "id": "text__1", "value": "{\"text\":" },
+                { "id": "numbers__1", "value": 7.5 }
+            ]
+        }])),
+    )
+    .await;
+
+    let output = env.run(&["query"]).await;
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+
+    let stdout = stdout(&output);
+    assert!(
+        stdout.contains("| 555 | Jane Doe | 2025  | {\"text\":"),
+        "{}",
+        stdout
+    );
+    assert!(stdout.contains("Total hours: 7.5"), "{}", stdout);
+    let stderr = stderr(&output);
+    assert!(
+        stderr.contains("Skipping column without an ID"),
+        "{}",
+        stderr
+    );
+    assert!(
+        stderr.contains("Could not decode the value of column text__1"),
+        "{}",
+        stderr
+    );
+}
+
+#[tokio::test]
+async fn query_shows_subitems_under_their_parent() {
+    let env = TestEnv::new().await;
+    env.respond(
+        "GetBoardStructure",
+        board(json!([{
+            "id": "555",
+            "name": "Jane Doe",
+            "group": { "id": "g2025" },
+            "column_values": [{ "id": "numbers__1", "value": "\"8\"" }],
+            "subitems": [{
+                "id": "556",
+                "name": "Review",
+                "column_values": [{ "id": "numbers", "value": "\"2\"" }]
+            }]
+        }])),
+    )
+    .await;
+
+    let output = env.run(&["query", "--with-subitems"]).await;
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+
+    let stdout = stdout(&output);
+    assert!(
+        stdout.contains("| 555     | Jane Doe | 2025  | 8                 |                |"),
+        "{}",
+        stdout
+    );
+    assert!(
+        stdout.contains("|   ↳ 556 |   Review | 2025  |                   | 2              |"),
+        "{}",
+        stdout
+    );
+    let requests = env.requests_for("GetBoardStructure").await;
+    assert_eq!(requests[0]["variables"]["withSubitems"], json!(true));
+}
+
+#[tokio::test]
+async fn update_hours_sets_the_hours_column() {
+    let env = TestEnv::new().await;
+    env.respond(
+        "ChangeSimpleColumnValue",
+        json!({ "change_simple_column_value": { "id": "555", "name": "Jane Doe" } }),
+    )
+    .await;
+
+    let output = env
+        .run(&["update-hours", "--id", "555", "--hours", "6.5"])
+        .await;
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert!(stdout(&output).contains("Updated Jane Doe (ID: 555) to 6.5 hours"));
+
+    let requests = env.requests_for("ChangeSimpleColumnValue").await;
+    assert_eq!(
+        requests[0]["variables"],
+        json!({ "boardId": 100, "itemId": "555", "columnId": "numbers__1", "value": "6.5" })
+    );
+}
+
+#[tokio::test]
+async fn query_resumes_from_a_cursor() {
+    let env = TestEnv::new().await;
+    env.respond("GetBoardStructure", board(json!([]))).await;
+    env.respond(
+        "next_items_page",
+        json!({ "next_items_page": {
+            "cursor": "page-3",
+            "items": [{
+                "id": "556",
+                "name": "John Doe",
+                "group": { "id": "g2025" },
+                "column_values": []
+            }]
+        } }),
+    )
+    .await;
+
+    let output = env
+        .run(&["query", "--cursor", "page-2", "--show-cursor"])
+        .await;
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert!(stdout(&output).contains("| 556 | John Doe |"));
+    assert!(stderr(&output).contains("Next cursor: page-3"));
+
+    // The first page is skipped, only the one after the cursor is fetched
+    let requests = env.requests_for("GetBoardStructure").await;
+    assert_eq!(requests[0]["variables"]["withItems"], json!(false));
+    let requests = env.requests_for("next_items_page").await;
+    assert_eq!(requests[0]["variables"]["cursor"], json!("page-2"));
+}
+
+#[tokio::test]
+async fn add_rejects_empty_text_fields() {
+    let env = TestEnv::new().await;
+
+    for (index, field) in [(2, "Item name"), (8, "Client"), (10, "Work item")] {
+        let mut args = ADD_ARGS;
+        args[index] = "  ";
+        let output = env.run(&args).await;
+
+        assert_eq!(output.status.code(), Some(3));
+        let stderr = stderr(&output);
+        assert!(
+            stderr.contains(&format!("{} must not be empty", field)),
+            "{}",
+            stderr
+        );
+    }
+    // Rejected before anything is sent
+    assert!(env.server.received_requests().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn search_combines_the_filters() {
+    let env = TestEnv::new().await;
+    let item = |id: &str, client: &str, wi: &str| {
+        json!({
+            "id": id,
+            "name": "Jane Doe",
+            "group": { "id": "g2025" },
+            "column_values": [
+                { "id": "text__1", "value": format!("\"{}\"", client) },
+                { "id": "text8__1", "value": format!("\"{}\"", wi) }
+            ]
+        })
+    };
+    env.respond(
+        "GetBoardStructure",
+        board(json!([
+            item("555", "Acme Corp", "WI-7"),
+            item("556", "Acme Corp", "WI-8"),
+            item("557", "Globex", "WI-7")
+        ])),
+    )
+    .await;
+
+    let output = env
+        .run(&["search", "--client", "acme", "--wi", "wi-7"])
+        .await;
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+
+    let stdout = stdout(&output);
+    assert!(stdout.contains("Found 1 items:"), "{}", stdout);
+    assert!(stdout.contains("| 555 |"), "{}", stdout);
+}
+
+#[tokio::test]
+async fn add_json_prints_only_the_created_item() {
+    let env = TestEnv::new().await;
+    env.respond("GetBoardGroups", groups()).await;
+    env.respond(
+        "CreateItem",
+        json!({ "create_item": { "id": "555", "name": "Jane Doe" } }),
+    )
+    .await;
+    env.respond("GetItemsOnDate", no_items_on_date()).await;
+
+    let mut args = ADD_ARGS.to_vec();
+    args.push("--json");
+    let output = env.run(&args).await;
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+
+    let result: Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(result, json!({ "id": "555", "name": "Jane Doe" }));
+    assert!(stderr(&output).contains("Item created successfully! ID: 555"));
+}
+
+#[tokio::test]
+async fn add_json_reports_graphql_errors_as_json() {
+    let env = TestEnv::new().await;
+    env.respond("GetBoardGroups", groups()).await;
+    env.respond("GetItemsOnDate", no_items_on_date()).await;
+    env.respond_with_errors(
+        "CreateItem",
+        json!([{
+            "message": "Invalid value for the hours column",
+            "extensions": { "code": "ColumnValueException" }
+        }]),
+    )
+    .await;
+
+    let mut args = ADD_ARGS.to_vec();
+    args.push("--json");
+    let output = env.run(&args).await;
+    assert_eq!(output.status.code(), Some(5));
+
+    let result: Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(
+        result["graphql_errors"],
+        json!([{ "message": "Invalid value for the hours column", "code": "ColumnValueException" }])
+    );
+}
+
+#[tokio::test]
+async fn add_falls_back_to_the_default_hours() {
+    let env = TestEnv::new().await;
+    env.respond("GetBoardGroups", groups()).await;
+    env.respond(
+        "CreateItem",
+        json!({ "create_item": { "id": "555", "name": "Jane Doe" } }),
+    )
+    .await;
+    env.respond("GetItemsOnDate", no_items_on_date()).await;
+
+    // Everything but --hours 7.5
+    let args: Vec<&str> = ADD_ARGS
+        .iter()
+        .copied()
+        .filter(|arg| !["--hours", "7.5"].contains(arg))
+        .collect();
+    let output = env.run(&args).await;
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert!(stdout(&output).contains("Logging the default of 8 hours"));
+
+    let requests = env.requests_for("CreateItem").await;
+    let column_values: Value =
+        serde_json::from_str(requests[0]["variables"]["columnValues"].as_str().unwrap()).unwrap();
+    assert_eq!(column_values["numbers__1"], json!("8"));
+}
+
+#[tokio::test]
+async fn http_errors_show_the_status_and_a_hint() {
+    let env = TestEnv::new().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(401).set_body_string("Not Authenticated"))
+        .mount(&env.server)
+        .await;
+
+    let output = env.run(&["groups"]).await;
+
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = stderr(&output);
+    assert!(
+        stderr.contains("Monday API returned 401 Unauthorized: Not Authenticated"),
+        "{}",
+        stderr
+    );
+    assert!(
+        stderr.contains("Check that the API key is valid"),
+        "{}",
+        stderr
+    );
+}
+
+#[tokio::test]
+async fn query_marks_archived_items() {
+    let env = TestEnv::new().await;
+    env.respond(
+        "GetBoardStructure",
+        board(json!([
+            { "id": "555", "name": "Jane Doe", "state": "active", "group": { "id": "g2025" } },
+            { "id": "556", "name": "John Doe", "state": "archived", "group": { "id": "g2025" } }
+        ])),
+    )
+    .await;
+
+    let output = env.run(&["query", "--include-archived"]).await;
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+
+    let stdout = stdout(&output);
+    assert!(
+        stdout.contains("| 555 | Jane Doe            |"),
+        "{}",
+        stdout
+    );
+    assert!(
+        stdout.contains("| 556 | John Doe (archived) |"),
+        "{}",
+        stdout
+    );
+    let requests = env.requests_for("GetBoardStructure").await;
+    assert!(requests[0]["variables"]["queryParams"].is_object());
+}
+
+#[tokio::test]
+async fn add_fills_in_values_from_a_template() {
+    let env = TestEnv::new().await;
+    env.append_config(
+        "\n[[templates]]\nname = \"acme\"\nactivity = \"billable\"\nclient = \"Acme\"\nwi = \"WI-7\"\n",
+    );
+    env.respond("GetBoardGroups", groups()).await;
+    env.respond(
+        "CreateItem",
+        json!({ "create_item": { "id": "555", "name": "Jane Doe" } }),
+    )
+    .await;
+    env.respond("GetItemsOnDate", no_items_on_date()).await;
+
+    let output = env
+        .run(&[
+            "add",
+            "--template",
+            "acme",
+            "--wi",
+            "WI-9",
+            "-n",
+            "Jane Doe",
+            "-d",
+            "2025-09-05",
+            "-y",
+            "2025",
+            "-H",
+            "8",
+        ])
+        .await;
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+
+    // Explicit options win over the template
+    let requests = env.requests_for("CreateItem").await;
+    let column_values: Value =
+        serde_json::from_str(requests[0]["variables"]["columnValues"].as_str().unwrap()).unwrap();
+    assert_eq!(column_values["text__1"], json!("Acme"));
+    assert_eq!(column_values["text8__1"], json!("WI-9"));
+    assert_eq!(column_values["status"], json!({ "index": 1 }));
+
+    let output = env
+        .run(&[
+            "add",
+            "--template",
+            "acme-typo",
+            "-n",
+            "Jane Doe",
+            "-d",
+            "2025-09-05",
+            "-y",
+            "2025",
+        ])
+        .await;
+    assert_eq!(output.status.code(), Some(3));
+    assert!(
+        stderr(&output).contains("Unknown template `acme-typo`, configured templates are: acme"),
+        "{}",
+        stderr(&output)
+    );
+}
+
+#[tokio::test]
+async fn bulk_add_reports_concurrent_results_in_file_order() {
+    let env = TestEnv::new().await;
+    env.respond("GetBoardGroups", groups()).await;
+    env.respond(
+        "CreateItem",
+        json!({ "create_item": { "id": "555", "name": "Jane Doe" } }),
+    )
+    .await;
+    let csv = env.dir.path().join("week.csv");
+    let mut content = String::from("year,name,activity,date,client,wi,hours\n");
+    for day in 1..=5 {
+        content.push_str(&format!(
+            "2025,Day {},billable,2025-09-0{},Acme,WI-7,8\n",
+            day, day
+        ));
+    }
+    fs::write(&csv, content).unwrap();
+
+    let output = env
+        .run(&[
+            "bulk-add",
+            "--file",
+            csv.to_str().unwrap(),
+            "--concurrency",
+            "3",
+        ])
+        .await;
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+
+    let stdout = stdout(&output);
+    let summary: Vec<&str> = stdout
+        .lines()
+        .filter(|line| line.starts_with("  ✅ line"))
+        .collect();
+    let expected: Vec<String> = (1..=5)
+        .map(|day| format!("  ✅ line {}: Day {} (ID: 555)", day + 1, day))
+        .collect();
+    assert_eq!(summary, expected);
+    assert_eq!(env.requests_for("CreateItem").await.len(), 5);
+}
+
+#[tokio::test]
+async fn progress_bars_stay_out_of_piped_output() {
+    let env = TestEnv::new().await;
+    let item = |id: &str| json!({ "id": id, "name": "Jane Doe", "group": { "id": "g2025" } });
+    let mut first_page = board(json!([item("555")]));
+    first_page["boards"][0]["items_page"]["cursor"] = json!("page-2");
+    env.respond("GetBoardStructure", first_page).await;
+    env.respond(
+        "next_items_page",
+        json!({ "next_items_page": { "cursor": null, "items": [item("556")] } }),
+    )
+    .await;
+    env.respond("GetBoardGroups", groups()).await;
+    env.respond(
+        "CreateItem",
+        json!({ "create_item": { "id": "557", "name": "Jane Doe" } }),
+    )
+    .await;
+
+    let output = env.run(&["query", "--all"]).await;
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert!(stdout(&output).contains("Found 2 items"));
+    assert!(!stdout(&output).contains("Fetching pages of items"));
+    assert!(!stderr(&output).contains("Fetching pages of items"));
+
+    let csv = env.dir.path().join("day.csv");
+    fs::write(
+        &csv,
+        "year,name,activity,date,client,wi,hours\n2025,Day 1,billable,2025-09-01,Acme,WI-7,8\n",
+    )
+    .unwrap();
+    let output = env
+        .run(&["bulk-add", "--file", csv.to_str().unwrap()])
+        .await;
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert!(!stdout(&output).contains("Creating items"));
+    assert!(!stderr(&output).contains("Creating items"));
+}
+
+#[tokio::test]
+async fn bulk_add_sends_nothing_when_a_row_has_an_empty_client() {
+    let env = TestEnv::new().await;
+    env.respond("GetBoardGroups", groups()).await;
+    env.respond(
+        "CreateItem",
+        json!({ "create_item": { "id": "555", "name": "Jane Doe" } }),
+    )
+    .await;
+    let csv = env.dir.path().join("week.csv");
+    fs::write(
+        &csv,
+        "year,name,activity,date,client,wi,hours\n\
+         2025,Day 1,billable,2025-09-01,Acme,WI-7,8\n\
+         2025,Day 2,billable,2025-09-02, ,WI-7,8\n",
+    )
+    .unwrap();
+
+    let output = env
+        .run(&["bulk-add", "--file", csv.to_str().unwrap()])
+        .await;
+    assert_eq!(output.status.code(), Some(3));
+    let stderr = stderr(&output);
+    assert!(
+        stderr.contains("line 3: Client must not be empty"),
+        "stderr: {}",
+        stderr
+    );
+    assert!(env.requests_for("CreateItem").await.is_empty());
+}
+
+#[tokio::test]
+async fn strict_mode_lists_missing_column_ids() {
+    let env = TestEnv::new().await;
+    env.respond(
+        "GetBoardColumns",
+        json!({ "boards": [{ "columns": [
+            { "id": "text__1", "title": "Client", "type": "text" },
+            { "id": "numbers__1", "title": "Hours", "type": "numbers" },
+            { "id": "date4", "title": "Date", "type": "date" },
+            { "id": "status", "title": "Status", "type": "status" }
+        ] }] }),
+    )
+    .await;
+    env.respond("GetBoardGroups", groups()).await;
+
+    let output = env.run(&["--strict", "groups"]).await;
+
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = stderr(&output);
+    assert!(
+        stderr.contains("  - work_item = \"text8__1\"\n  - person = \"person\"\n"),
+        "{}",
+        stderr
+    );
+    assert!(env.requests_for("GetBoardGroups").await.is_empty());
+}
+
+#[tokio::test]
+async fn recent_shows_the_latest_entries_of_the_user() {
+    let env = TestEnv::new().await;
+    let item = |id: &str, user: u64, date: &str| {
+        json!({
+            "id": id,
+            "name": format!("Entry {}", id),
+            "group": { "id": "g2025" },
+            "column_values": [
+                { "id": "person", "value": format!("{{\"personsAndTeams\":[{{\"id\":{},\"kind\":\"person\"}}]}}", user) },
+                { "id": "date4", "value": format!("{{\"date\":\"{}\"}}", date) }
+            ]
+        })
+    };
+    env.respond(
+        "GetBoardStructure",
+        board(json!([
+            item("1", 42, "2025-09-01"),
+            item("2", 42, "2025-09-03"),
+            item("3", 7, "2025-09-04"),
+            item("4", 42, "2025-09-02")
+        ])),
+    )
+    .await;
+    env.respond(
+        "users(",
+        json!({ "users": [{ "id": "42", "name": "Jane Doe" }] }),
+    )
+    .await;
+
+    let output = env.run(&["recent", "-n", "2"]).await;
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+
+    let stdout = stdout(&output);
+    let ids: Vec<&str> = stdout
+        .lines()
+        .filter_map(|line| line.strip_prefix("| "))
+        .filter_map(|line| line.split_whitespace().next())
+        .filter(|id| id.chars().all(|c| c.is_ascii_digit()))
+        .collect();
+    assert_eq!(ids, ["2", "4"], "{}", stdout);
+}
+
+#[tokio::test]
+async fn add_refuses_to_create_a_duplicate() {
+    let env = TestEnv::new().await;
+    env.respond("GetBoardGroups", groups()).await;
+    env.respond(
+        "GetItemsOnDate",
+        json!({ "boards": [{ "items_page": { "cursor": null, "items": [{
+            "id": "554",
+            "name": "Jane Doe",
+            "group": { "id": "g2025" },
+            "column_values": [
+                { "id": "date4", "value": "{\"date\":\"2025-09-05\"}" },
+                { "id": "text__1", "value": "\"Acme\"" }
+            ]
+        }] } }] }),
+    )
+    .await;
+    env.respond(
+        "CreateItem",
+        json!({ "create_item": { "id": "555", "name": "Jane Doe" } }),
+    )
+    .await;
+
+    let output = env.run(&ADD_ARGS).await;
+    assert_eq!(output.status.code(), Some(1));
+    assert!(
+        stderr(&output).contains("already exists in group 2025 (ID: 554)"),
+        "{}",
+        stderr(&output)
+    );
+    assert!(env.requests_for("CreateItem").await.is_empty());
+
+    let mut args = ADD_ARGS.to_vec();
+    args.push("--allow-duplicate");
+    let output = env.run(&args).await;
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert_eq!(env.requests_for("CreateItem").await.len(), 1);
+}
+
+#[tokio::test]
+async fn query_shows_only_the_selected_columns() {
+    let env = TestEnv::new().await;
+    env.respond(
+        "GetBoardStructure",
+        board(json!([{
+            "id": "555",
+            "name": "Jane Doe",
+            "group": { "id": "g2025" },
+            "column_values": [
+                { "id": "date4", "value": "{\"date\":\"2025-09-05\"}" },
+                { "id": "text__1", "value": "\"Acme\"" },
+                { "id": "numbers__1", "value": "\"7.5\"" }
+            ]
+        }])),
+    )
+    .await;
+
+    let output = env.run(&["query", "--columns", "hours,date4"]).await;
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    let stdout = stdout(&output);
+    assert!(
+        stdout.contains("| ID  | Name     | Group | Column numbers__1 | Column date4 |"),
+        "{}",
+        stdout
+    );
+
+    let output = env.run(&["query", "--columns", "hours,customer"]).await;
+    assert_eq!(output.status.code(), Some(3));
+    assert!(
+        stderr(&output).contains("Unknown column: customer"),
+        "{}",
+        stderr(&output)
+    );
+}
+
+#[tokio::test]
+async fn update_renames_the_item() {
+    let env = TestEnv::new().await;
+    env.respond(
+        "GetItemSummary",
+        json!({ "items": [{ "id": "555", "name": "Old name", "group": { "id": "g1", "title": "2024" } }] }),
+    )
+    .await;
+    env.respond(
+        "ChangeSimpleColumnValue",
+        json!({ "change_simple_column_value": { "id": "555", "name": "New name" } }),
+    )
+    .await;
+
+    let output = env
+        .run(&["update", "--id", "555", "--name", " New name "])
+        .await;
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert!(stdout(&output).contains("Renamed item 555 from \"Old name\" to \"New name\""));
+
+    let requests = env.requests_for("ChangeSimpleColumnValue").await;
+    assert_eq!(requests[0]["variables"]["columnId"], json!("name"));
+    assert_eq!(requests[0]["variables"]["value"], json!("New name"));
+
+    let output = env.run(&["update", "--id", "555", "--name", "  "]).await;
+    assert_eq!(output.status.code(), Some(3));
+}
+
+#[tokio::test]
+async fn query_offline_shows_the_cached_items() {
+    let env = TestEnv::new().await;
+    let output = env.run(&["query", "--offline"]).await;
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("run query once without --offline"));
+
+    env.respond(
+        "GetBoardStructure",
+        board(json!([{
+            "id": "555",
+            "name": "Jane Doe",
+            "group": { "id": "g2025" },
+            "column_values": [{ "id": "numbers__1", "value": "\"7.5\"" }]
+        }])),
+    )
+    .await;
+    let online = env.run(&["query"]).await;
+    assert!(online.status.success(), "stderr: {}", stderr(&online));
+
+    env.server.reset().await;
+    let offline = env.run(&["query", "--offline"]).await;
+    assert!(offline.status.success(), "stderr: {}", stderr(&offline));
+    assert_eq!(stdout(&offline), stdout(&online));
+    assert!(stderr(&offline).contains("Offline: showing the items fetched"));
+    assert!(env.server.received_requests().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn stats_breaks_the_hours_down_by_activity() {
+    let env = TestEnv::new().await;
+    let item = |id: &str, status: i64, hours: &str| {
+        json!({
+            "id": id,
+            "name": "Jane Doe",
+            "group": { "id": "g2025" },
+            "column_values": [
+                { "id": "status", "value": format!("{{\"index\":{}}}", status) },
+                { "id": "numbers__1", "value": format!("\"{}\"", hours) }
+            ]
+        })
+    };
+    env.respond(
+        "GetBoardStructure",
+        board_with_columns(
+            json!([{
+                "id": "status",
+                "type": "status",
+                "settings_str": "{\"labels\":{\"1\":\"Billable\"}}"
+            }]),
+            json!([item("1", 1, "6"), item("2", 3, "2"), item("3", 1, "0")]),
+        ),
+    )
+    .await;
+
+    let output = env.run(&["stats", "--json"]).await;
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    let stats: Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(
+        stats,
+        json!([
+            { "activity": "Billable", "hours": 6.0, "percent": 75.0 },
+            { "activity": "education", "hours": 2.0, "percent": 25.0 }
+        ])
+    );
+}
+
+#[tokio::test]
+async fn mine_drops_the_items_of_other_people() {
+    let env = TestEnv::new().await;
+    let item = |id: &str, person: u64, hours: &str| {
+        json!({
+            "id": id,
+            "name": format!("Item {}", id),
+            "group": { "id": "g2025" },
+            "column_values": [
+                {
+                    "id": "person",
+                    "value": format!("{{\"personsAndTeams\":[{{\"id\":{},\"kind\":\"person\"}}]}}", person)
+                },
+                { "id": "status", "value": "{\"index\":1}" },
+                { "id": "numbers__1", "value": format!("\"{}\"", hours) }
+            ]
+        })
+    };
+    env.respond(
+        "GetBoardStructure",
+        board(json!([
+            item("1", 42, "8"),
+            item("2", 7, "5"),
+            item("3", 42, "2")
+        ])),
+    )
+    .await;
+
+    let output = env.run(&["query", "--mine", "--format", "json"]).await;
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    let items: Value = serde_json::from_slice(&output.stdout).unwrap();
+    let ids: Vec<&str> = items
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| item["id"].as_str().unwrap())
+        .collect();
+    assert_eq!(ids, ["1", "3"]);
+
+    let output = env.run(&["stats", "--mine", "--format", "json"]).await;
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    let stats: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        stats,
+        json!([{ "activity": "billable", "hours": 10.0, "percent": 100.0 }])
+    );
+}
+
+#[tokio::test]
+async fn format_picks_the_renderer_of_every_read_command() {
+    let env = TestEnv::new().await;
+    let item = json!({
+        "id": "1",
+        "name": "Jane Doe",
+        "group": { "id": "g2025" },
+        "column_values": [
+            { "id": "text__1", "value": "\"Acme\"" },
+            { "id": "numbers__1", "value": "\"8\"" }
+        ]
+    });
+    env.respond("GetBoardStructure", board(json!([item.clone()])))
+        .await;
+    let mut details = item;
+    details["board"] = json!({ "groups": [{ "id": "g2025", "title": "2025" }], "columns": [] });
+    env.respond("GetItem(", json!({ "items": [details] })).await;
+
+    let output = env.run(&["query", "--format", "csv"]).await;
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        "ID,Name,Group,text__1,numbers__1\n1,Jane Doe,2025,Acme,8\n"
+    );
+
+    let output = env.run(&["get", "--id", "1", "--format", "json"]).await;
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    let shown: Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(shown["group"], json!("2025"));
+    assert_eq!(shown["column_values"]["text__1"], json!("Acme"));
+
+    let output = env.run(&["summary", "--format", "csv"]).await;
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert_eq!(stdout(&output), "Client,Hours,Items\nAcme,8,1\n");
+    let output = env.run(&["stats", "--format", "csv"]).await;
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        "Activity,Hours,Share\n(no status),8,100.0%\n"
+    );
+
+    let output = env.run(&["summary", "--format", "markdown"]).await;
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        "| Client | Hours | Items |\n| --- | --- | --- |\n| Acme | 8 | 1 |\n| Total | 8 | 1 |\n"
+    );
+
+    // --append still needs a CSV file to add to
+    let output = env
+        .run(&[
+            "query",
+            "--format",
+            "json",
+            "--output",
+            "items.json",
+            "--append",
+        ])
+        .await;
+    assert_eq!(output.status.code(), Some(3));
+    assert!(stderr(&output).contains("--append only adds to CSV files"));
+}
+
+#[tokio::test]
+async fn an_empty_board_list_suggests_checking_the_board_id() {
+    let env = TestEnv::new().await;
+    env.respond("GetBoardStructure", json!({ "boards": [] }))
+        .await;
+    env.respond("GetBoardGroups", json!({ "boards": [] })).await;
+
+    for args in [&["query"][..], &ADD_ARGS[..]] {
+        let output = env.run(args).await;
+        assert_eq!(output.status.code(), Some(6));
+        let stderr = stderr(&output);
+        assert!(
+            stderr.contains("Board 100 was not found"),
+            "stderr: {}",
+            stderr
+        );
+        assert!(stderr.contains("Check board_id in the config"));
+    }
+}
+
+#[tokio::test]
+async fn board_names_are_resolved_to_their_id() {
+    let env = TestEnv::new().await;
+    env.respond(
+        "GetBoards(",
+        json!({ "boards": [
+            { "id": "100", "name": "Time" },
+            { "id": "300", "name": "Shared" },
+            { "id": "400", "name": "Shared" }
+        ] }),
+    )
+    .await;
+    env.respond("GetBoardStructure", board(json!([]))).await;
+
+    let output = env.run(&["--board-name", "Time", "query"]).await;
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    let requests = env.requests_for("GetBoardStructure").await;
+    assert_eq!(requests[0]["variables"]["boardId"], json!([100]));
+    assert_eq!(env.requests_for("GetBoards(").await.len(), 1);
+
+    // The second time the cached ID is used without listing the boards
+    let output = env.run(&["--board-name", "Time", "query"]).await;
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert_eq!(env.requests_for("GetBoardStructure").await.len(), 2);
+    assert_eq!(env.requests_for("GetBoards(").await.len(), 1);
+
+    let output = env.run(&["--board-name", "Shared", "query"]).await;
+    assert_eq!(output.status.code(), Some(3));
+    let stderr_text = stderr(&output);
+    assert!(stderr_text.contains("2 boards are named Shared, set board_id to the one you mean"));
+    assert!(stderr_text.contains("Shared: 300") && stderr_text.contains("Shared: 400"));
+
+    let output = env.run(&["--board-name", "Nope", "query"]).await;
+    assert_eq!(output.status.code(), Some(6));
+    let stderr_text = stderr(&output);
+    assert!(stderr_text.contains("No board named Nope was found"));
+    assert!(stderr_text.contains("Check board_name in the config"));
+    assert_eq!(env.requests_for("GetBoardStructure").await.len(), 2);
+}
+
+#[tokio::test]
+async fn cache_clear_removes_every_cache_file() {
+    let env = TestEnv::new().await;
+    env.respond(
+        "GetBoards(",
+        json!({ "boards": [{ "id": "100", "name": "Time" }] }),
+    )
+    .await;
+    env.respond("GetBoardStructure", board(json!([]))).await;
+    env.respond("GetBoardGroups", groups()).await;
+    env.respond(
+        "CreateItem",
+        json!({ "create_item": { "id": "555", "name": "Jane Doe" } }),
+    )
+    .await;
+    env.respond("GetItemsOnDate", no_items_on_date()).await;
+    assert!(env
+        .run(&["--board-name", "Time", "query"])
+        .await
+        .status
+        .success());
+    assert!(env.run(&ADD_ARGS).await.status.success());
+
+    let cache_dir = env.dir.path().join("cache").join("monday-claim");
+    let files = |dir: &std::path::Path| {
+        let mut names: Vec<String> = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        names.sort();
+        names
+    };
+    assert_eq!(
+        files(&cache_dir),
+        ["board-100.json", "board-names.json", "items-100.json"]
+    );
+
+    let output = env.run(&["cache", "clear"]).await;
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert!(stdout(&output).contains("Removed 3 cache file(s)"));
+    assert!(files(&cache_dir).is_empty());
+    // The last-used values of --use-last aren't cached data
+    assert!(env
+        .dir
+        .path()
+        .join("config")
+        .join("monday-claim")
+        .join("state.json")
+        .exists());
+}
+
+#[tokio::test]
+async fn errors_never_show_the_api_key() {
+    let env = TestEnv::new().await;
+    env.respond_with_errors(
+        "GetBoardStructure",
+        json!([{ "message": "Token test-key is not authorized" }]),
+    )
+    .await;
+
+    let output = env.run(&["query"]).await;
+    assert_eq!(output.status.code(), Some(5));
+    let stderr = stderr(&output);
+    assert!(
+        stderr.contains("Token *** is not authorized"),
+        "stderr: {}",
+        stderr
+    );
+    assert!(!stderr.contains("test-key"));
+}
+
+#[tokio::test]
+async fn set_merges_raw_column_values() {
+    let env = TestEnv::new().await;
+    env.respond("GetBoardGroups", groups()).await;
+    env.respond(
+        "CreateItem",
+        json!({ "create_item": { "id": "555", "name": "Jane Doe" } }),
+    )
+    .await;
+    env.respond("GetItemsOnDate", no_items_on_date()).await;
+    env.respond(
+        "ChangeMultipleColumnValues",
+        json!({ "change_multiple_column_values": { "id": "555", "name": "Jane Doe" } }),
+    )
+    .await;
+
+    let mut args = ADD_ARGS.to_vec();
+    args.extend([
+        "--set",
+        r#"link__1={"url":"https://example.com","text":"Docs"}"#,
+        "--set",
+        "numbers__1=8",
+    ]);
+    let output = env.run(&args).await;
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    let requests = env.requests_for("CreateItem").await;
+    let column_values: Value =
+        serde_json::from_str(requests[0]["variables"]["columnValues"].as_str().unwrap()).unwrap();
+    assert_eq!(
+        column_values["link__1"],
+        json!({ "url": "https://example.com", "text": "Docs" })
+    );
+    assert_eq!(column_values["numbers__1"], json!(8));
+    assert_eq!(column_values["text__1"], json!("Acme"));
+
+    let output = env
+        .run(&["update", "--id", "555", "--set", "text__1=\"Globex\""])
+        .await;
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert!(stdout(&output).contains("Updated text__1 of Jane Doe (ID: 555)"));
+    let requests = env.requests_for("ChangeMultipleColumnValues").await;
+    assert_eq!(
+        requests[0]["variables"]["columnValues"],
+        json!(r#"{"text__1":"Globex"}"#)
+    );
+
+    let output = env
+        .run(&["update", "--id", "555", "--set", "text__1={oops"])
+        .await;
+    assert_eq!(output.status.code(), Some(3));
+    assert!(stderr(&output).contains("the value is not valid JSON"));
+}
+
+#[tokio::test]
+async fn add_defaults_the_date_to_today_in_the_configured_timezone() {
+    let env = TestEnv::new().await;
+    env.append_config("timezone = \"Pacific/Kiritimati\"\n");
+    env.respond("GetBoardGroups", groups()).await;
+    env.respond(
+        "CreateItem",
+        json!({ "create_item": { "id": "555", "name": "Jane Doe" } }),
+    )
+    .await;
+    env.respond("GetItemsOnDate", no_items_on_date()).await;
+
+    let args: Vec<&str> = ADD_ARGS
+        .iter()
+        .copied()
+        .filter(|arg| !["--date", "2025-09-05"].contains(arg))
+        .collect();
+    let output = env.run(&args).await;
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+
+    // UTC+14, so usually a different day than the machine running the test
+    let today = chrono::Utc::now()
+        .with_timezone(&chrono_tz::Pacific::Kiritimati)
+        .date_naive()
+        .to_string();
+    assert!(stdout(&output).contains(&format!(
+        "Using today's date {} (Pacific/Kiritimati)",
+        today
+    )));
+    let requests = env.requests_for("CreateItem").await;
+    let column_values: Value =
+        serde_json::from_str(requests[0]["variables"]["columnValues"].as_str().unwrap()).unwrap();
+    assert_eq!(column_values["date4"], json!({ "date": today }));
+}
+
+#[tokio::test]
+async fn fields_narrow_the_fetched_column_values() {
+    let env = TestEnv::new().await;
+    env.respond(
+        "GetBoardColumns",
+        json!({ "boards": [{ "columns": [
+            { "id": "date4", "title": "Date", "type": "date" },
+            { "id": "numbers__1", "title": "Hours", "type": "numbers" }
+        ] }] }),
+    )
+    .await;
+    env.respond(
+        "GetBoardStructure",
+        board_with_columns(
+            json!([
+                { "id": "date4", "type": "date" },
+                { "id": "numbers__1", "type": "numbers" }
+            ]),
+            json!([]),
+        ),
+    )
+    .await;
+
+    let output = env.run(&["query", "--fields", "hours,date4"]).await;
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    let requests = env.requests_for("GetBoardStructure").await;
+    assert_eq!(
+        requests[0]["variables"]["columnIds"],
+        json!(["numbers__1", "date4"])
+    );
+
+    // Without --fields every column is fetched
+    env.run(&["query"]).await;
+    let requests = env.requests_for("GetBoardStructure").await;
+    assert_eq!(requests[1]["variables"]["columnIds"], Value::Null);
+
+    // A typo fails before any items are requested, --all or not
+    let output = env
+        .run(&["query", "--all", "--fields", "hours,text99"])
+        .await;
+    assert_eq!(output.status.code(), Some(3));
+    assert!(stderr(&output).contains("Unknown column: text99"));
+    assert_eq!(env.requests_for("GetBoardStructure").await.len(), 2);
+}
+
+#[tokio::test]
+async fn export_writes_a_workbook() {
+    let env = TestEnv::new().await;
+    let item = |id: &str, group: &str, date: &str| {
+        json!({
+            "id": id,
+            "name": "Jane Doe",
+            "group": { "id": group },
+            "column_values": [
+                { "id": "date4", "value": format!("{{\"date\":\"{}\"}}", date) },
+                { "id": "numbers__1", "value": "\"7.5\"" }
+            ]
+        })
+    };
+    let mut response = board_with_columns(
+        json!([{ "id": "date4", "title": "Date", "type": "date" }]),
+        json!([
+            item("1", "g2024", "2024-12-30"),
+            item("2", "g2025", "2025-01-02"),
+            item("3", "g2025", "2025-01-03")
+        ]),
+    );
+    response["boards"][0]["groups"] = json!([
+        { "id": "g2024", "title": "2024" },
+        { "id": "g2025", "title": "2025" },
+        { "id": "g2026", "title": "2026" }
+    ]);
+    env.respond("GetBoardStructure", response).await;
+
+    let path = env.dir.path().join("items.xlsx");
+    let output = env.run(&["export", path.to_str().unwrap()]).await;
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert!(stdout(&output).contains("Wrote 3 items in 2 sheet(s)"));
+    // An XLSX file is a ZIP archive
+    assert!(fs::read(&path).unwrap().starts_with(b"PK"));
+
+    let output = env
+        .run(&[
+            "export",
+            path.to_str().unwrap(),
+            "--year",
+            "2025",
+            "--overwrite",
+        ])
+        .await;
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert!(stdout(&output).contains("Wrote 2 items in 1 sheet(s)"));
+}
+
+#[tokio::test]
+async fn existing_files_are_only_replaced_when_confirmed_up_front() {
+    let env = TestEnv::new().await;
+    env.respond("GetBoardStructure", board(json!([]))).await;
+    let path = env.dir.path().join("items.json");
+    fs::write(&path, "keep me").unwrap();
+    let path = path.to_str().unwrap();
+
+    // Stdin isn't a terminal, so nobody could be asked
+    let output = env
+        .run(&["query", "--format", "json", "--output", path])
+        .await;
+    assert_eq!(output.status.code(), Some(3));
+    assert!(stderr(&output).contains("pass --overwrite or --yes"));
+    assert_eq!(fs::read_to_string(path).unwrap(), "keep me");
+    assert!(env.requests_for("GetBoardStructure").await.is_empty());
+
+    let output = env
+        .run(&["--yes", "query", "--format", "json", "--output", path])
+        .await;
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert_eq!(fs::read_to_string(path).unwrap(), "[]\n");
+}
+
+#[tokio::test]
+async fn since_last_only_shows_new_items() {
+    let env = TestEnv::new().await;
+    let board_of = |ids: &[&str]| {
+        let items: Vec<Value> = ids
+            .iter()
+            .map(|id| json!({ "id": id, "name": format!("Item {}", id), "group": { "id": "g2025" } }))
+            .collect();
+        board(json!(items))
+    };
+    let names = |output: &Output| {
+        let items: Value = serde_json::from_str(&stdout(output)).unwrap();
+        items
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|item| item["name"].as_str().unwrap().to_string())
+            .collect::<Vec<_>>()
+    };
+
+    env.respond("GetBoardStructure", board_of(&["501", "502"]))
+        .await;
+    let output = env.run(&["query", "--since-last", "--json"]).await;
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert_eq!(names(&output), ["Item 501", "Item 502"]);
+
+    env.server.reset().await;
+    env.respond("GetBoardStructure", board_of(&["501", "502", "1003"]))
+        .await;
+    // A filtered run doesn't move the marker past the items it left out
+    let output = env
+        .run(&["query", "--since-last", "--mine", "--json"])
+        .await;
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert!(names(&output).is_empty());
+    let output = env.run(&["query", "--since-last", "--json"]).await;
+    assert_eq!(names(&output), ["Item 1003"]);
+    let output = env.run(&["query", "--since-last", "--json"]).await;
+    assert!(names(&output).is_empty());
+
+    let output = env
+        .run(&["query", "--since-last", "--reset-marker", "--json"])
+        .await;
+    assert_eq!(names(&output).len(), 3);
+}
+
+#[tokio::test]
+async fn ping_reports_the_round_trip_times() {
+    let env = TestEnv::new().await;
+    env.respond("me", json!({ "me": { "id": "42", "name": "Jane Doe" } }))
+        .await;
+
+    let output = env.run(&["ping", "--count", "2"]).await;
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    let stdout = stdout(&output);
+    assert!(stdout.contains("API key valid (authenticated as Jane Doe)"));
+    assert!(stdout.contains("2 request(s) to http://"));
+    assert!(stdout.contains("min ") && stdout.contains("avg ") && stdout.contains("max "));
+    assert_eq!(env.requests_for("me").await.len(), 2);
+}
+
+#[tokio::test]
+async fn login_rejects_an_empty_api_key() {
+    let env = TestEnv::new().await;
+
+    // Stdin is empty, so nothing reaches the keychain
+    let output = env.run(&["login"]).await;
+    assert_eq!(output.status.code(), Some(3));
+    assert!(stderr(&output).contains("The API key can't be empty"));
+}
+
+#[tokio::test]
+async fn column_map_overrides_the_configured_column_ids() {
+    let env = TestEnv::new().await;
+    env.respond("GetBoardGroups", groups()).await;
+    env.respond(
+        "CreateItem",
+        json!({ "create_item": { "id": "555", "name": "Jane Doe" } }),
+    )
+    .await;
+    env.respond("GetItemsOnDate", no_items_on_date()).await;
+
+    let mut args = ADD_ARGS.to_vec();
+    args.extend(["--column-map", "client=text5, hours=numbers2"]);
+    let output = env.run(&args).await;
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+
+    let requests = env.requests_for("CreateItem").await;
+    let column_values: Value =
+        serde_json::from_str(requests[0]["variables"]["columnValues"].as_str().unwrap()).unwrap();
+    assert_eq!(column_values["text5"], json!("Acme"));
+    assert_eq!(column_values["numbers2"], json!("7.5"));
+    assert_eq!(column_values["text8__1"], json!("WI-7"));
+    assert!(column_values.get("text__1").is_none());
+
+    let mut args = ADD_ARGS.to_vec();
+    args.extend(["--column-map", "customer=text5"]);
+    let output = env.run(&args).await;
+    assert_eq!(output.status.code(), Some(3));
+    assert!(stderr(&output).contains("--column-map: unknown setting `customer`"));
+}
+
+#[tokio::test]
+async fn show_null_marks_unset_columns() {
+    let env = TestEnv::new().await;
+    env.respond(
+        "GetBoardStructure",
+        board(json!([{
+            "id": "555",
+            "name": "Jane Doe",
+            "group": { "id": "g2025" },
+            "column_values": [
+                { "id": "text__1", "value": "\"\"" },
+                { "id": "text8__1", "value": null }
+            ]
+        }])),
+    )
+    .await;
+
+    let output = env.run(&["query", "--format", "markdown"]).await;
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert!(stdout(&output).contains("| 555 | Jane Doe | 2025 |  |  |"));
+
+    let output = env
+        .run(&["query", "--format", "markdown", "--show-null"])
+        .await;
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert!(
+        stdout(&output).contains("| 555 | Jane Doe | 2025 |  | — |"),
+        "{}",
+        stdout(&output)
+    );
+
+    // Only tables have cells to mark, whichever flag picked the format
+    for args in [
+        &["query", "--show-null", "--json"][..],
+        &["query", "--show-null", "--format", "json"][..],
+        &["query", "--show-null", "--csv", "items.csv"][..],
+        &["query", "--show-null", "--format", "csv"][..],
+    ] {
+        let output = env.run(args).await;
+        assert_eq!(output.status.code(), Some(3), "{:?}", args);
+        assert!(
+            stderr(&output).contains("--show-null only applies to the table and Markdown formats")
+        );
+    }
+}
+
+#[tokio::test]
+async fn an_empty_board_reads_the_same_in_markdown() {
+    let env = TestEnv::new().await;
+    env.respond("GetBoardStructure", board(json!([]))).await;
+
+    for format in ["table", "markdown"] {
+        let output = env.run(&["query", "--format", format]).await;
+        assert!(output.status.success(), "stderr: {}", stderr(&output));
+        assert!(stdout(&output).contains("No items found in the board."));
+        assert!(!stdout(&output).contains("| ID |"));
+    }
+}
+
+#[tokio::test]
+async fn delete_reports_each_item_and_the_failures() {
+    let env = TestEnv::new().await;
+    Mock::given(method("POST"))
+        .and(body_string_contains("GetItemSummary"))
+        .and(body_string_contains("\"itemId\":[\"11\"]"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(json!({ "data": { "items": [
+            { "id": "11", "name": "Test run", "group": { "id": "g2025", "title": "2025" } }
+        ] } })),
+        )
+        .mount(&env.server)
+        .await;
+    env.respond("GetItemSummary", json!({ "items": [] })).await;
+    env.respond(
+        "DeleteItem",
+        json!({ "delete_item": { "id": "11", "name": "Test run" } }),
+    )
+    .await;
+
+    let output = env.run(&["delete", "--id", "11,12", "--force"]).await;
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = stdout(&output);
+    assert!(stdout.contains("✅ 11: deleted Test run"), "{}", stdout);
+    assert!(stdout.contains("❌ 12: failed: No item found with ID: 12"));
+    assert!(stdout.contains("Deleted 1 of 2 items"));
+    assert!(stderr(&output).contains("1 of 2 items failed"));
+
+    let requests = env.requests_for("DeleteItem").await;
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0]["variables"]["itemId"], json!("11"));
+}
+
+#[tokio::test]
+async fn delete_without_a_terminal_needs_force() {
+    let env = TestEnv::new().await;
+    env.respond(
+        "GetItemSummary",
+        json!({ "items": [
+            { "id": "11", "name": "Test run", "group": { "id": "g2025", "title": "2025" } }
+        ] }),
+    )
+    .await;
+    env.respond(
+        "DeleteItem",
+        json!({ "delete_item": { "id": "11", "name": "Test run" } }),
+    )
+    .await;
+
+    let output = env.run(&["delete", "--id", "11"]).await;
+    assert_eq!(output.status.code(), Some(3));
+    assert!(stderr(&output).contains("pass --force or --yes"));
+    assert!(env.requests_for("DeleteItem").await.is_empty());
+}
+
+#[tokio::test]
+async fn too_complex_queries_are_retried_with_a_lower_limit() {
+    let env = TestEnv::new().await;
+    Mock::given(method("POST"))
+        .and(body_string_contains("GetBoardStructure"))
+        .and(body_string_contains("\"limit\":25"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "data": board(json!([])) })))
+        .mount(&env.server)
+        .await;
+    env.respond_with_errors(
+        "GetBoardStructure",
+        json!([{
+            "message": "Query has complexity of 6000000, which exceeds max complexity of 5000000",
+            "extensions": { "code": "maxComplexityExceeded" }
+        }]),
+    )
+    .await;
+
+    let output = env.run(&["query", "--limit", "100"]).await;
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    let stdout = stdout(&output);
+    assert!(stdout.contains("retrying with 50 items per page"));
+    assert!(stdout.contains("retrying with 25 items per page"));
+    assert_eq!(env.requests_for("GetBoardStructure").await.len(), 3);
+
+    // The retry warnings stay out of output meant for other tools
+    let output = env
+        .run(&["query", "--limit", "100", "--format", "json"])
+        .await;
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    let items: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(items, json!([]));
+    assert!(stderr(&output).contains("retrying with 25 items per page"));
+
+    // No retries left once the limit would have to drop below 25
+    env.append_config("complexity_retries = 1\n");
+    let output = env.run(&["query", "--limit", "100"]).await;
+    assert_eq!(output.status.code(), Some(5));
+}
+
+#[tokio::test]
+async fn csv_append_adds_only_new_rows_under_the_existing_header() {
+    let env = TestEnv::new().await;
+    let board = |items: Value| board(items);
+    let item = |id: &str, hours: &str| {
+        json!({
+            "id": id,
+            "name": "Jane Doe",
+            "group": { "id": "g2025" },
+            "column_values": [
+                { "id": "text__1", "value": "\"Acme\"" },
+                { "id": "numbers__1", "value": format!("\"{}\"", hours) }
+            ]
+        })
+    };
+    let path = env.dir.path().join("items.csv");
+    let path = path.to_str().unwrap();
+
+    env.respond("GetBoardStructure", board(json!([item("1", "8")])))
+        .await;
+    let output = env.run(&["query", "--csv", path]).await;
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+
+    env.server.reset().await;
+    env.respond(
+        "GetBoardStructure",
+        board(json!([item("1", "8"), item("2", "4")])),
+    )
+    .await;
+    let output = env
+        .run(&["query", "--csv", path, "--append", "--dedup"])
+        .await;
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert!(stdout(&output).contains("Appended 1 items to"));
+    assert_eq!(
+        fs::read_to_string(path).unwrap(),
+        "ID,Name,Group,text__1,numbers__1\n1,Jane Doe,2025,Acme,8\n2,Jane Doe,2025,Acme,4\n"
+    );
+}
+
+#[tokio::test]
+async fn completions_cover_the_subcommands() {
+    let env = TestEnv::new().await;
+
+    let output = env.run(&["completions", "bash"]).await;
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    let script = stdout(&output);
+    assert!(script.contains("complete -F _monday__claim"));
+    assert!(script.contains("log-week"));
+    assert!(script.contains("--since-last"));
+
+    let output = env.run(&["completions", "tcsh"]).await;
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[tokio::test]
+async fn requests_identify_the_tool_and_send_the_key() {
+    let env = TestEnv::new().await;
+    env.respond("me", json!({ "me": { "id": "42", "name": "Jane Doe" } }))
+        .await;
+
+    let output = env.run(&["ping", "--count", "2"]).await;
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    let requests = env.server.received_requests().await.unwrap();
+    assert_eq!(requests.len(), 2);
+    for request in requests {
+        let header = |name: &str| request.headers.get(name).unwrap().to_str().unwrap();
+        assert_eq!(
+            header("user-agent"),
+            concat!("monday-claim/", env!("CARGO_PKG_VERSION"))
+        );
+        assert_eq!(header("authorization"), "test-key");
+        assert_eq!(header("content-type"), "application/json");
+    }
+}
+
+#[tokio::test]
+async fn the_user_agent_suffix_is_added_to_the_user_agent() {
+    let env = TestEnv::new().await;
+    env.append_config("user_agent_suffix = \"acme-timesheets\"\n");
+    env.respond("me", json!({ "me": { "id": "42", "name": "Jane Doe" } }))
+        .await;
+
+    let output = env.run(&["ping", "--count", "1"]).await;
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    let requests = env.server.received_requests().await.unwrap();
+    assert_eq!(
+        requests[0].headers.get("user-agent").unwrap(),
+        concat!(
+            "monday-claim/",
+            env!("CARGO_PKG_VERSION"),
+            " acme-timesheets"
+        )
+    );
+}
+
+#[tokio::test]
+async fn board_id_flag_overrides_the_configured_board() {
+    let env = TestEnv::new().await;
+    env.respond("GetBoardGroups", groups()).await;
+    env.respond(
+        "CreateItem",
+        json!({ "create_item": { "id": "555", "name": "Jane Doe" } }),
+    )
+    .await;
+    env.respond("GetItemsOnDate", no_items_on_date()).await;
+
+    let mut args = vec!["--board-id", "200"];
+    args.extend(ADD_ARGS);
+    let output = env.run(&args).await;
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    let requests = env.requests_for("CreateItem").await;
+    assert_eq!(requests[0]["variables"]["boardId"], json!(200));
+
+    let output = env.run(&["--board-id", "abc", "groups"]).await;
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[tokio::test]
+async fn repl_runs_each_line_with_one_client() {
+    let env = TestEnv::new().await;
+    env.respond("GetBoardGroups", groups()).await;
+    env.respond("me", json!({ "me": { "id": "42", "name": "Jane Doe" } }))
+        .await;
+
+    let output = env
+        .run_with_input(
+            &["repl"],
+            "groups\n--dry-run groups\nbogus\nping -n 1\nexit\ngroups\n",
+        )
+        .await;
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    let stdout = stdout(&output);
+    assert_eq!(stdout.matches("Found 2 groups:").count(), 1, "{}", stdout);
+    assert!(stdout.contains("API key valid (authenticated as Jane Doe)"));
+    let stderr = stderr(&output);
+    assert!(stderr.contains("--dry-run applies to the whole session"));
+    assert!(stderr.contains("unrecognized subcommand 'bogus'"));
+}